@@ -0,0 +1,361 @@
+use solution::{json_string, Answer};
+use std::char;
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Where the manifests are kept, relative to the repository
+pub const DIRECTORY: &str = "answers";
+
+/// The answers recorded for one input of a day, kept as `day_NN.toml`
+///
+/// Numbers are stored as integers, text as a string and images as an array
+/// with a string per row.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Manifest {
+    /// Fingerprint of the input the answers belong to
+    pub input: String,
+    parts: BTreeMap<usize, Answer>,
+}
+
+/// How an answer compares to the recorded one
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Check {
+    Correct,
+    Wrong {
+        expected: Answer,
+    },
+    /// Nothing was recorded for this part
+    Unrecorded,
+    /// The answers were recorded for a different input
+    OtherInput,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Check::Correct => write!(f, "correct"),
+            Check::Wrong { .. } => write!(f, "wrong"),
+            Check::Unrecorded => write!(f, "unrecorded"),
+            Check::OtherInput => write!(f, "other input"),
+        }
+    }
+}
+
+impl Manifest {
+    /// A manifest without answers for `input`
+    pub fn new(input: &str) -> Manifest {
+        Manifest { input: fingerprint(input), parts: BTreeMap::new() }
+    }
+
+    pub fn get(&self, part: usize) -> Option<&Answer> {
+        self.parts.get(&part)
+    }
+
+    pub fn set(&mut self, part: usize, answer: Answer) {
+        self.parts.insert(part, answer);
+    }
+
+    /// Compare the answer to a part for `input` with the recorded one
+    pub fn check(&self, input: &str, part: usize, answer: &Answer) -> Check {
+        if self.input != fingerprint(input) {
+            return Check::OtherInput;
+        }
+        match self.get(part) {
+            Some(expected) if expected == answer => Check::Correct,
+            Some(expected) => Check::Wrong { expected: expected.clone() },
+            None => Check::Unrecorded,
+        }
+    }
+
+    pub fn path(directory: &Path, day: &str) -> PathBuf {
+        directory.join(format!("{}.toml", day))
+    }
+
+    /// The manifest of a day, `None` when nothing was recorded
+    pub fn load(
+        directory: &Path,
+        day: &str,
+    ) -> Result<Option<Manifest>, ManifestError> {
+        let path = Manifest::path(directory, day);
+        match fs::read_to_string(&path) {
+            Ok(text) => text.parse().map(Some).map_err(|error| match error {
+                ManifestError::Syntax { line, message, .. } => {
+                    ManifestError::Syntax { path: Some(path), line, message }
+                }
+                error => error,
+            }),
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => {
+                Ok(None)
+            }
+            Err(error) => Err(ManifestError::Io(path, error)),
+        }
+    }
+
+    pub fn save(
+        &self,
+        directory: &Path,
+        day: &str,
+    ) -> Result<(), ManifestError> {
+        let path = Manifest::path(directory, day);
+        fs::create_dir_all(directory)
+            .and_then(|_| fs::write(&path, self.to_string()))
+            .map_err(|error| ManifestError::Io(path, error))
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "input = {}", json_string(&self.input))?;
+        for (part, answer) in &self.parts {
+            write!(f, "part{} = ", part)?;
+            match answer {
+                Answer::Number(number) => writeln!(f, "{}", number)?,
+                Answer::Text(text) => writeln!(f, "{}", json_string(text))?,
+                Answer::Image(rows) => {
+                    writeln!(f, "[")?;
+                    for row in rows {
+                        writeln!(f, "    {},", json_string(row))?;
+                    }
+                    writeln!(f, "]")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = ManifestError;
+
+    /// Parse the subset of TOML the manifests are written in
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut input = None;
+        let mut parts = BTreeMap::new();
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()));
+        while let Some((number, line)) = lines.next() {
+            let error = |message: &str| ManifestError::syntax(number, message);
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut assignment = line.splitn(2, '=').map(str::trim);
+            let key = assignment.next().unwrap();
+            let value = assignment
+                .next()
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| error("no value"))?;
+
+            let answer = if value == "[" {
+                let mut rows = Vec::new();
+                loop {
+                    let (number, row) = lines
+                        .next()
+                        .ok_or_else(|| error("the image is never closed"))?;
+                    match row {
+                        "]" => break,
+                        "" => continue,
+                        row => rows.push(
+                            parse_string(row.trim_end_matches(','))
+                                .ok_or_else(|| {
+                                    ManifestError::syntax(
+                                        number,
+                                        "not a string",
+                                    )
+                                })?,
+                        ),
+                    }
+                }
+                Answer::Image(rows)
+            } else if value.starts_with('"') {
+                Answer::Text(
+                    parse_string(value).ok_or_else(|| error("not a string"))?,
+                )
+            } else {
+                Answer::Number(
+                    value.parse().map_err(|_| error("not a number"))?,
+                )
+            };
+
+            match (key, answer) {
+                ("input", Answer::Text(fingerprint)) => {
+                    input = Some(fingerprint)
+                }
+                ("part1", answer) => {
+                    parts.insert(1, answer);
+                }
+                ("part2", answer) => {
+                    parts.insert(2, answer);
+                }
+                _ => return Err(error(&format!("unexpected key {}", key))),
+            }
+        }
+        let input =
+            input.ok_or_else(|| ManifestError::syntax(0, "no input given"))?;
+        Ok(Manifest { input, parts })
+    }
+}
+
+/// The contents of a basic string, quotes included
+fn parse_string(quoted: &str) -> Option<String> {
+    if quoted.len() < 2 || !quoted.starts_with('"') || !quoted.ends_with('"') {
+        return None;
+    }
+    let mut text = String::new();
+    let mut chars = quoted[1..quoted.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        text.push(match chars.next()? {
+            '"' => '"',
+            '\\' => '\\',
+            'n' => '\n',
+            't' => '\t',
+            'u' => {
+                let code: String = chars.by_ref().take(4).collect();
+                char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+            }
+            _ => return None,
+        });
+    }
+    Some(text)
+}
+
+/// FNV-1a of the input, ignoring trailing whitespace
+pub fn fingerprint(input: &str) -> String {
+    let hash =
+        input.trim_end().bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(PathBuf, io::Error),
+    Syntax { path: Option<PathBuf>, line: usize, message: String },
+}
+
+impl ManifestError {
+    fn syntax(line: usize, message: &str) -> ManifestError {
+        ManifestError::Syntax { path: None, line, message: message.to_string() }
+    }
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ManifestError::Io(path, error) => {
+                write!(f, "could not access {}: {}", path.display(), error)
+            }
+            ManifestError::Syntax { path, line, message } => {
+                if let Some(path) = path {
+                    write!(f, "{}:", path.display())?;
+                }
+                write!(f, "{}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl error::Error for ManifestError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn manifest() -> Manifest {
+        let mut manifest = Manifest::new("12\n14\n");
+        manifest.set(
+            2,
+            Answer::Image(vec!["# \"".to_string(), " #\\".to_string()]),
+        );
+        manifest.set(1, Answer::Number(-42));
+        manifest
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = manifest().to_string();
+        assert_eq!(
+            text,
+            "input = \"f81c3c8724bbd735\"\npart1 = -42\npart2 = [\n    \"# \\\"\",\n    \" #\\\\\",\n]\n"
+        );
+        assert_eq!(text.parse::<Manifest>().unwrap(), manifest());
+
+        let mut text_answer = Manifest::new("");
+        text_answer.set(1, Answer::Text("01\u{2588}\n".to_string()));
+        let text = text_answer.to_string();
+        assert_eq!(text.parse::<Manifest>().unwrap(), text_answer);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse =
+            |text: &str| text.parse::<Manifest>().unwrap_err().to_string();
+        assert_eq!(parse("input = \"0\"\npart1 = 12a"), "2: not a number");
+        assert_eq!(
+            parse("input = \"0\"\npart3 = 1"),
+            "2: unexpected key part3"
+        );
+        assert_eq!(
+            parse("input = \"0\"\npart2 = [\n  \"#\",\n"),
+            "2: the image is never closed"
+        );
+        assert_eq!(parse("part1 = 1"), "0: no input given");
+    }
+
+    #[test]
+    fn test_check() {
+        let manifest = manifest();
+        let input = "12\n14";
+        assert_eq!(
+            manifest.check(input, 1, &Answer::Number(-42)),
+            Check::Correct
+        );
+        assert_eq!(
+            manifest.check(input, 1, &Answer::Number(42)),
+            Check::Wrong { expected: Answer::Number(-42) }
+        );
+        assert_eq!(
+            manifest.check("12", 1, &Answer::Number(-42)),
+            Check::OtherInput
+        );
+        let mut manifest = Manifest::new(input);
+        manifest.set(2, Answer::Number(0));
+        assert_eq!(
+            manifest.check(input, 1, &Answer::Number(0)),
+            Check::Unrecorded
+        );
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let directory = env::temp_dir().join("aoc_answers_test");
+        let _ = fs::remove_file(Manifest::path(&directory, "day_01"));
+        assert!(Manifest::load(&directory, "day_01").unwrap().is_none());
+
+        manifest().save(&directory, "day_01").unwrap();
+        assert_eq!(
+            Manifest::load(&directory, "day_01").unwrap(),
+            Some(manifest())
+        );
+
+        fs::write(Manifest::path(&directory, "day_02"), "part1 =").unwrap();
+        let error = Manifest::load(&directory, "day_02").unwrap_err();
+        assert!(
+            error.to_string().ends_with("day_02.toml:1: no value"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use solution::json_string;
+use std::time::Duration;
+
+/// Runs of a part when no amount is given
+pub const RUNS: usize = 10;
+
+/// The wall time of every run of one part of a day
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Timing {
+    pub day: &'static str,
+    pub part: usize,
+    pub times: Vec<Duration>,
+}
+
+impl Timing {
+    pub fn min(&self) -> Duration {
+        self.times.iter().cloned().min().unwrap_or_default()
+    }
+
+    /// The middle time, or the mean of the middle two for an even amount
+    pub fn median(&self) -> Duration {
+        let mut times = self.times.clone();
+        times.sort();
+        if times.is_empty() {
+            return Duration::default();
+        }
+        let middle = times.len() / 2;
+        match times.len() % 2 {
+            1 => times[middle],
+            _ => (times[middle - 1] + times[middle]) / 2,
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        self.times.iter().cloned().max().unwrap_or_default()
+    }
+}
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+/// A Markdown table with a row per part, times in milliseconds
+pub fn markdown(timings: &[Timing]) -> String {
+    let mut table = String::from(
+        "| day | part | runs | min (ms) | median (ms) | max (ms) |\n\
+         |-----|-----:|-----:|---------:|------------:|---------:|\n",
+    );
+    for timing in timings {
+        table.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            timing.day,
+            timing.part,
+            timing.times.len(),
+            milliseconds(timing.min()),
+            milliseconds(timing.median()),
+            milliseconds(timing.max())
+        ));
+    }
+    table
+}
+
+/// A JSON array with an object per part, times in milliseconds
+pub fn json(timings: &[Timing]) -> String {
+    let objects: Vec<_> = timings
+        .iter()
+        .map(|timing| {
+            format!(
+                "{{\"day\":{},\"part\":{},\"runs\":{},\"min_ms\":{},\
+                 \"median_ms\":{},\"max_ms\":{}}}",
+                json_string(timing.day),
+                timing.part,
+                timing.times.len(),
+                milliseconds(timing.min()),
+                milliseconds(timing.median()),
+                milliseconds(timing.max())
+            )
+        })
+        .collect();
+    format!("[{}]", objects.join(",\n "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(micros: &[u64]) -> Timing {
+        let times =
+            micros.iter().map(|&us| Duration::from_micros(us)).collect();
+        Timing { day: "day_16", part: 2, times }
+    }
+
+    #[test]
+    fn test_statistics() {
+        let odd = timing(&[30, 10, 20]);
+        assert_eq!(odd.min(), Duration::from_micros(10));
+        assert_eq!(odd.median(), Duration::from_micros(20));
+        assert_eq!(odd.max(), Duration::from_micros(30));
+        assert_eq!(
+            timing(&[40, 10, 20, 30]).median(),
+            Duration::from_micros(25)
+        );
+        assert_eq!(timing(&[]).median(), Duration::default());
+    }
+
+    #[test]
+    fn test_reports() {
+        let timings = vec![timing(&[1500, 2500, 1000])];
+        assert_eq!(
+            markdown(&timings).lines().nth(2),
+            Some("| day_16 | 2 | 3 | 1.000 | 1.500 | 2.500 |")
+        );
+        assert_eq!(
+            json(&timings),
+            "[{\"day\":\"day_16\",\"part\":2,\"runs\":3,\"min_ms\":1.000,\
+             \"median_ms\":1.500,\"max_ms\":2.500}]"
+        );
+    }
+}
@@ -0,0 +1,451 @@
+use answers::{Check, ManifestError};
+use bench;
+use input::{InputError, Provider};
+use intcode::asm::AsmError;
+use intcode::snapshot::SnapshotError;
+use intcode::{IntcodeError, ParseError};
+use solution::{json_string, Answer};
+use std::error;
+use std::fmt;
+use std::io;
+
+pub const USAGE: &str = "\
+usage: advent_of_code_2019 run <day>... | all [--input <file> | -]
+                                [--input-dir <directory>] [--part 1|2]
+                                [--format text|json] [--check]
+       advent_of_code_2019 record <day>... | all [--input <file> | -]
+                                [--input-dir <directory>] [--part 1|2]
+       advent_of_code_2019 bench <day>... | all [--input <file> | -]
+                                [--input-dir <directory>] [--part 1|2]
+                                [--format text|json] [--runs <n>]
+       advent_of_code_2019 list
+       advent_of_code_2019 <intcode tool> <arguments>...
+
+Days are given as day_07, 07 or 7. The input of a single day can be replaced
+with a file, or with stdin when the file is -. Inputs of any day are read as
+day_NN.txt from the input directory, which defaults to $AOC_INPUT_DIR. Without
+either, the inputs compiled into the binary are used.
+
+With --check the answers are compared with the ones recorded in
+answers/day_NN.toml, record runs the days and updates those manifests.
+
+bench runs every part 10 times unless --runs is given, and reports the
+minimum, median and maximum wall time as a Markdown table or as JSON.
+
+Intcode tools: ascii, asm, disasm, debug, trace, profile, exec, exec-as, sweep,
+phases, bench-intcode, snapshot, resume";
+
+/// How the answers are printed
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    /// A line per part, images on the lines after it
+    Text,
+    /// An array with an object per day
+    Json,
+}
+
+/// The commands that run days, they share their arguments but not every
+/// option
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Command {
+    Run,
+    Record,
+    Bench,
+}
+
+impl Command {
+    fn name(self) -> &'static str {
+        match self {
+            Command::Run => "run",
+            Command::Record => "record",
+            Command::Bench => "bench",
+        }
+    }
+
+    /// Whether the command takes the given option
+    fn takes(self, option: &str) -> bool {
+        match option {
+            "--input" | "--input-dir" | "--part" => true,
+            "--format" => self != Command::Record,
+            "--check" => self == Command::Run,
+            "--runs" => self == Command::Bench,
+            _ => false,
+        }
+    }
+}
+
+/// The arguments of the `run`, `record` and `bench` commands
+#[derive(Debug, PartialEq, Eq)]
+pub struct Run {
+    /// Names of the days, in the order they were given
+    pub days: Vec<&'static str>,
+    /// The provider given on the command line, if any
+    pub input: Option<Provider>,
+    /// Only this part, both when `None`
+    pub part: Option<usize>,
+    pub format: Format,
+    /// Compare the answers with the recorded ones
+    pub check: bool,
+    /// How often `bench` runs every part
+    pub runs: usize,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    NoCommand,
+    UnknownCommand(String),
+    /// A day that does not exist, with the ones that do
+    UnknownDay(String, Vec<&'static str>),
+    UnknownOption(String),
+    /// An option that exists, but not for the command it was given to
+    UnsupportedOption(&'static str, String),
+    MissingValue(String),
+    InvalidPart(String),
+    InvalidFormat(String),
+    InvalidRuns(String),
+    NoDays,
+    /// An input was given for more than a single day
+    SharedInput,
+    Input(InputError),
+    Manifest(ManifestError),
+    /// The amount of answers that differ from the recorded ones
+    WrongAnswers(usize),
+    /// An argument of an Intcode tool, with what is wrong with it
+    InvalidArgument(String),
+    /// A program file that is not a list of numbers
+    Program(String, ParseError),
+    /// A program that faulted, with the file it was read from
+    Intcode(String, IntcodeError),
+    Assembly(String, AsmError),
+    Snapshot(String, SnapshotError),
+    Write(String, io::Error),
+    Terminal(io::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::NoCommand => write!(f, "no command given"),
+            CliError::UnknownCommand(command) => {
+                write!(f, "unknown command {}", command)
+            }
+            CliError::UnknownDay(day, days) => write!(
+                f,
+                "unknown day {}, the days are: {} or all",
+                day,
+                days.join(", ")
+            ),
+            CliError::UnknownOption(option) => {
+                write!(f, "unknown option {}", option)
+            }
+            CliError::UnsupportedOption(command, option) => {
+                write!(f, "{} does not take {}", command, option)
+            }
+            CliError::MissingValue(option) => {
+                write!(f, "{} needs a value", option)
+            }
+            CliError::InvalidPart(part) => {
+                write!(f, "there is no part {}, only 1 and 2", part)
+            }
+            CliError::InvalidFormat(format) => {
+                write!(f, "unknown format {}, use text or json", format)
+            }
+            CliError::InvalidRuns(runs) => {
+                write!(f, "cannot run {} times, give a positive number", runs)
+            }
+            CliError::NoDays => write!(f, "no days given to run"),
+            CliError::SharedInput => {
+                write!(f, "an input can only be given when running one day")
+            }
+            CliError::Input(error) => write!(f, "{}", error),
+            CliError::Manifest(error) => write!(f, "{}", error),
+            CliError::WrongAnswers(1) => write!(f, "1 answer is wrong"),
+            CliError::WrongAnswers(wrong) => {
+                write!(f, "{} answers are wrong", wrong)
+            }
+            CliError::InvalidArgument(message) => write!(f, "{}", message),
+            CliError::Program(path, error) => write!(f, "{}: {}", path, error),
+            CliError::Intcode(path, error) => write!(f, "{}: {}", path, error),
+            CliError::Assembly(path, error) => write!(f, "{}: {}", path, error),
+            CliError::Snapshot(path, error) => write!(f, "{}: {}", path, error),
+            CliError::Write(path, error) => {
+                write!(f, "could not write {}: {}", path, error)
+            }
+            CliError::Terminal(error) => {
+                write!(f, "could not talk to the terminal: {}", error)
+            }
+        }
+    }
+}
+
+impl error::Error for CliError {}
+
+impl CliError {
+    /// Whether the arguments were wrong, so the usage helps
+    pub fn is_usage(&self) -> bool {
+        !matches!(
+            self,
+            CliError::Input(_)
+                | CliError::Manifest(_)
+                | CliError::WrongAnswers(_)
+                | CliError::Program(..)
+                | CliError::Intcode(..)
+                | CliError::Assembly(..)
+                | CliError::Snapshot(..)
+                | CliError::Write(..)
+                | CliError::Terminal(_)
+        )
+    }
+}
+
+impl From<ManifestError> for CliError {
+    fn from(error: ManifestError) -> Self {
+        CliError::Manifest(error)
+    }
+}
+
+impl From<InputError> for CliError {
+    fn from(error: InputError) -> Self {
+        CliError::Input(error)
+    }
+}
+
+/// Parse the arguments of one of the commands that run days, `days` are the
+/// names of all days
+pub fn parse_run(
+    command: Command,
+    args: &[String],
+    days: &[&'static str],
+) -> Result<Run, CliError> {
+    let mut run = Run {
+        days: Vec::new(),
+        input: None,
+        part: None,
+        format: Format::Text,
+        check: false,
+        runs: bench::RUNS,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") && !command.takes(arg) && known(arg) {
+            return Err(CliError::UnsupportedOption(
+                command.name(),
+                arg.clone(),
+            ));
+        }
+        let mut value =
+            || args.next().ok_or_else(|| CliError::MissingValue(arg.clone()));
+        match arg.as_str() {
+            "--input" => {
+                run.input = Some(match value()?.as_str() {
+                    "-" => Provider::Stdin,
+                    path => Provider::File(path.to_string()),
+                })
+            }
+            "--input-dir" => {
+                run.input = Some(Provider::Directory(value()?.clone()))
+            }
+            "--part" => {
+                let part = value()?;
+                run.part = match part.as_str() {
+                    "1" => Some(1),
+                    "2" => Some(2),
+                    _ => return Err(CliError::InvalidPart(part.clone())),
+                }
+            }
+            "--format" => {
+                let format = value()?;
+                run.format = match format.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    _ => return Err(CliError::InvalidFormat(format.clone())),
+                }
+            }
+            "--check" => run.check = true,
+            "--runs" => {
+                let runs = value()?;
+                run.runs = match runs.parse() {
+                    Ok(runs) if runs > 0 => runs,
+                    _ => return Err(CliError::InvalidRuns(runs.clone())),
+                }
+            }
+            "all" => run.days.extend(days),
+            option if option.starts_with('-') => {
+                return Err(CliError::UnknownOption(option.to_string()))
+            }
+            day => run.days.push(find_day(day, days)?),
+        }
+    }
+
+    if run.days.is_empty() {
+        return Err(CliError::NoDays);
+    }
+    let per_day = run.input.as_ref().is_none_or(Provider::per_day);
+    if !per_day && run.days.len() > 1 {
+        return Err(CliError::SharedInput);
+    }
+    Ok(run)
+}
+
+/// Whether any of the commands takes the option
+fn known(option: &str) -> bool {
+    Command::Run.takes(option) || Command::Bench.takes(option)
+}
+
+/// The name of a day given as `day_07`, `07` or `7`
+fn find_day(
+    day: &str,
+    days: &[&'static str],
+) -> Result<&'static str, CliError> {
+    let name = match day.parse::<u32>() {
+        Ok(number) => format!("day_{:02}", number),
+        Err(_) => day.to_string(),
+    };
+    days.iter()
+        .find(|&&known| known == name)
+        .cloned()
+        .ok_or_else(|| CliError::UnknownDay(day.to_string(), days.to_vec()))
+}
+
+/// The answers of a day as a JSON object, with a key for every part and for
+/// the check of every part when there are `checks`
+pub fn json(
+    day: &str,
+    answers: &[(usize, Answer)],
+    checks: &[Check],
+) -> String {
+    let mut fields = vec![format!("\"day\":{}", json_string(day))];
+    for (part, answer) in answers {
+        fields.push(format!("\"part{}\":{}", part, answer.to_json()));
+    }
+    for ((part, _), check) in answers.iter().zip(checks) {
+        let check = json_string(&check.to_string());
+        fields.push(format!("\"check{}\":{}", part, check));
+    }
+    format!("{{{}}}", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAYS: &[&str] = &["day_01", "day_02", "day_07"];
+
+    fn parse_as(command: Command, args: &str) -> Result<Run, CliError> {
+        let args: Vec<_> = args.split_whitespace().map(String::from).collect();
+        parse_run(command, &args, DAYS)
+    }
+
+    fn parse(args: &str) -> Result<Run, CliError> {
+        parse_as(Command::Run, args)
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(
+            parse("day_02 7 01").unwrap(),
+            Run {
+                days: vec!["day_02", "day_07", "day_01"],
+                input: None,
+                part: None,
+                format: Format::Text,
+                check: false,
+                runs: bench::RUNS,
+            }
+        );
+        assert_eq!(parse("all").unwrap().days, DAYS);
+    }
+
+    #[test]
+    fn test_options() {
+        assert_eq!(
+            parse("--part 2 day_07 --input inputs/7.txt").unwrap(),
+            Run {
+                days: vec!["day_07"],
+                input: Some(Provider::File("inputs/7.txt".to_string())),
+                part: Some(2),
+                format: Format::Text,
+                check: false,
+                runs: bench::RUNS,
+            }
+        );
+        assert_eq!(parse("1 --input -").unwrap().input, Some(Provider::Stdin));
+        assert_eq!(
+            parse("all --input-dir inputs").unwrap().input,
+            Some(Provider::Directory("inputs".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = parse("day_03").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown day day_03, the days are: day_01, day_02, day_07 or all"
+        );
+        assert!(matches!(parse(""), Err(CliError::NoDays)));
+        assert!(matches!(parse("1 --part 3"), Err(CliError::InvalidPart(_))));
+        assert!(matches!(
+            parse_as(Command::Bench, "1 --runs 0"),
+            Err(CliError::InvalidRuns(_))
+        ));
+        assert!(matches!(
+            parse("1 --format xml"),
+            Err(CliError::InvalidFormat(_))
+        ));
+        assert!(matches!(parse("1 --part"), Err(CliError::MissingValue(_))));
+        assert!(matches!(parse("1 --fast"), Err(CliError::UnknownOption(_))));
+        assert!(matches!(
+            parse("all --input day_01.txt"),
+            Err(CliError::SharedInput)
+        ));
+    }
+
+    #[test]
+    fn test_options_per_command() {
+        let unsupported =
+            |command, args| parse_as(command, args).unwrap_err().to_string();
+
+        assert_eq!(
+            unsupported(Command::Run, "1 --runs 5"),
+            "run does not take --runs"
+        );
+        assert_eq!(
+            unsupported(Command::Record, "1 --format json --check"),
+            "record does not take --format"
+        );
+        assert_eq!(
+            unsupported(Command::Record, "1 --check"),
+            "record does not take --check"
+        );
+        assert_eq!(
+            unsupported(Command::Bench, "1 --check"),
+            "bench does not take --check"
+        );
+        assert_eq!(parse_as(Command::Bench, "1 --runs 3").unwrap().runs, 3);
+        assert_eq!(
+            parse_as(Command::Record, "1 --part 1 --input-dir inputs")
+                .unwrap()
+                .part,
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let answers = vec![
+            (1, Answer::Number(2562)),
+            (2, Answer::Image(vec!["# ".to_string(), " #".to_string()])),
+        ];
+        assert_eq!(
+            json("day_08", &answers, &[]),
+            r##"{"day":"day_08","part1":2562,"part2":["# "," #"]}"##
+        );
+        assert_eq!(
+            json("day_08", &answers[..1], &[Check::Correct]),
+            r#"{"day":"day_08","part1":2562,"check1":"correct"}"#
+        );
+        let run = parse("7 --format json --check").unwrap();
+        assert_eq!((run.format, run.check), (Format::Json, true));
+    }
+}
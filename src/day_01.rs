@@ -67,32 +67,42 @@
 /// (Calculate the fuel requirements for each module separately, then add them
 /// all up at the end.)
 
-const INPUT: &str = include_str!("../input/day_01.txt");
+use solution::{Answer, Solution};
 
-pub fn run() {
-    let module_masses = get_input();
+pub const INPUT: &str = include_str!("../input/day_01.txt");
 
-    let mut total_fuel_modules = 0;
-    let mut total_all_fuel = 0;
+pub struct Puzzle;
 
-    for module_mass in module_masses.iter() {
-        let mut fuel_needed = fuel_requirement(module_mass);
-        total_fuel_modules += fuel_needed;
-        while fuel_needed > 0 {
-            total_all_fuel += fuel_needed;
-            fuel_needed = fuel_requirement(&fuel_needed);
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let module_masses = get_input(input);
+
+        let total_fuel_modules: i32 =
+            module_masses.iter().map(fuel_requirement).sum();
+        total_fuel_modules.into()
+    }
+
+    fn part2(&self, input: &str) -> Answer {
+        let module_masses = get_input(input);
+
+        let mut total_all_fuel = 0;
+        for module_mass in module_masses.iter() {
+            let mut fuel_needed = fuel_requirement(module_mass);
+            while fuel_needed > 0 {
+                total_all_fuel += fuel_needed;
+                fuel_needed = fuel_requirement(&fuel_needed);
+            }
         }
+        total_all_fuel.into()
     }
-    println!("The total fuel needed for all the modules is: {}", total_fuel_modules);
-    println!("The total fuel needed for all the modules and fuel is: {}", total_all_fuel);
 }
 
 fn fuel_requirement(mass: &i32) -> i32 {
     (mass / 3) - 2
 }
 
-fn get_input() -> Vec<i32> {
-    INPUT.lines()
+fn get_input(input: &str) -> Vec<i32> {
+    input.lines()
         .map(|line| line.parse())
         .filter_map(Result::ok)
         .collect()
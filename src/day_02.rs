@@ -133,37 +133,51 @@
 /// 19690720. What is 100 * noun + verb? (For example, if noun=12 and verb=2,
 /// the answer would be 1202.)
 use intcode;
+use intcode::sweep::Patch;
+use intcode::symbolic;
+use intcode::InstructionSet;
+use solution::{Answer, Solution};
 
-const INPUT: &str = include_str!("../input/day_02.txt");
+pub const INPUT: &str = include_str!("../input/day_02.txt");
 
-pub fn run() {
-    let base_program = intcode::load(INPUT);
+pub struct Puzzle;
 
-    // reproduce the "1202 program alarm" by setting position 1 & 2
-    {
-        let mut program = base_program.clone();
-        program.insert(1, 12);
-        program.insert(2, 2);
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let mut program = intcode::load(input);
+        // the gravity assist program only knows how to add and multiply
+        let dialect = intcode::isa::STANDARD.only(&[1, 2, 99]);
 
-        let runner = intcode::start(program);
-        println!("The value left at position 0 after reproducing the \"1202 program alarm\" is: {}", runner.program[&0]);
+        // reproduce the "1202 program alarm" by setting position 1 & 2
+        program.set(1, 12);
+        program.set(2, 2);
+
+        let program = execute(program, &dialect);
+        program[0].into()
     }
 
-    // try to find the output 19690720
-    {
-        'finder: for noun in 0..=99 {
-            for verb in 0..=99 {
-                let mut program = base_program.clone();
-                program.insert(1, noun);
-                program.insert(2, verb);
+    fn part2(&self, input: &str) -> Answer {
+        let base_program = intcode::load(input);
 
-                let runner = intcode::start(program);
-                if runner.program[&0] == 19690720 {
-                    let answer = 100 * noun + verb;
-                    println!("The input noun and verb to produce the output 19690720 is: {}", answer);
-                    break 'finder;
-                }
-            }
-        }
+        // find the output 19690720, the noun and verb are at position 1 & 2
+        let patches = [
+            Patch { address: 1, values: 0..=99 },
+            Patch { address: 2, values: 0..=99 },
+        ];
+        let values = symbolic::solve(&base_program, &patches, 0, 19690720)
+            .expect("No noun and verb produce 19690720");
+        (100 * values[0] + values[1]).into()
     }
 }
+
+/// Run the program to its end, returning the memory it leaves behind
+fn execute(
+    program: intcode::Program,
+    dialect: &InstructionSet,
+) -> intcode::Program {
+    intcode::Runner::new(program)
+        .run_with(&mut intcode::io::iter(vec![]), &mut Vec::new(), dialect)
+        .and_then(intcode::Runner::or_fault)
+        .unwrap()
+        .program
+}
@@ -101,11 +101,12 @@
 /// What is the fewest combined steps the wires must take to reach an
 /// intersection?
 use regex::Regex;
+use solution::{Answer, Solution};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 
-const INPUT: &str = include_str!("../input/day_03.txt");
+pub const INPUT: &str = include_str!("../input/day_03.txt");
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 enum Direction {
@@ -142,37 +143,46 @@ impl Hash for Wire {
 type Point = (i32, i32);
 type Grid = HashMap<Point, HashSet<Wire>>;
 
-pub fn run() {
-    let wires = get_input();
+pub struct Puzzle;
 
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let wire_grid = lay_wires(input);
+
+        let closest = wire_grid
+            .iter()
+            .filter(|(_, wires_present)| wires_present.len() == 2)
+            .map(|((x, y), _)| x.abs() + y.abs()) // convert to Manhattan distances
+            .min()
+            .unwrap();
+        closest.into()
+    }
+
+    fn part2(&self, input: &str) -> Answer {
+        let wire_grid = lay_wires(input);
+
+        let fewest_steps = wire_grid
+            .iter()
+            .filter(|(_, wires_present)| wires_present.len() == 2)
+            .map(|(_, wires)| {
+                wires
+                    .iter()
+                    .map(|wire: &Wire| wire.distance) // extract distances
+                    .sum::<u32>()
+            }) // sum them
+            .min()
+            .unwrap();
+        fewest_steps.into()
+    }
+}
+
+/// Lay every wire of the input on the same grid
+fn lay_wires(input: &str) -> Grid {
     let mut wire_grid = HashMap::new();
-    for (wire_number, moves) in wires.iter().enumerate() {
+    for (wire_number, moves) in get_input(input).iter().enumerate() {
         wire_grid = lay_wire(wire_number, moves, wire_grid)
     }
-
-    let closest = wire_grid
-        .iter()
-        .filter(|(_, wires_present)| wires_present.len() == 2)
-        .map(|((x, y), _)| x.abs() + y.abs()) // convert to Manhattan distances
-        .min()
-        .unwrap();
-    println!("The Manhattan distance to closest intersection is: {}", closest);
-
-    let fewest_steps = wire_grid
-        .iter()
-        .filter(|(_, wires_present)| wires_present.len() == 2)
-        .map(|(_, wires)| {
-            wires
-                .iter()
-                .map(|wire: &Wire| wire.distance) // extract distances
-                .sum::<u32>()
-        }) // sum them
-        .min()
-        .unwrap();
-    println!(
-        "The fewest combined steps for an intersection is: {}",
-        fewest_steps
-    );
+    wire_grid
 }
 
 fn lay_wire(
@@ -203,8 +213,8 @@ fn lay_wire(
     wire_grid
 }
 
-fn get_input() -> Vec<Vec<Move>> {
-    INPUT
+fn get_input(input: &str) -> Vec<Vec<Move>> {
+    input
         .lines()
         .map(|line| {
             line.split(',').filter_map(|item| convert_to_move(item)).collect()
@@ -40,6 +40,10 @@
 ///
 /// How many different passwords within the range given in your puzzle input
 /// meet all of the criteria?
+use solution::{Answer, Solution};
+use std::ops::RangeInclusive;
+
+pub const INPUT: &str = include_str!("../input/day_04.txt");
 
 struct Digits {
     number: u32,
@@ -75,28 +79,29 @@ impl Iterator for Digits {
     }
 }
 
-pub fn run() {
-    let start = 264360;
-    let end = 746325;
+pub struct Puzzle;
 
-    let mut valid_passwords = 0;
-    let mut valid_stricter_passwords = 0;
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let valid_passwords =
+            get_input(input).filter(|&number| is_valid(number)).count();
+        valid_passwords.into()
+    }
 
-    for number in start..=end {
-        if is_valid(number) {
-            valid_passwords += 1;
-        }
-        if is_valid_with_stricter_criteria(number) {
-            valid_stricter_passwords += 1;
-        }
+    fn part2(&self, input: &str) -> Answer {
+        let valid_stricter_passwords = get_input(input)
+            .filter(|&number| is_valid_with_stricter_criteria(number))
+            .count();
+        valid_stricter_passwords.into()
     }
+}
 
-    println!(
-        "Amount of passwords meeting the criteria in the given range: {}",
-        valid_passwords);
-    println!(
-        "Amount of passwords meeting the stricter criteria in the given range: {}",
-        valid_stricter_passwords);
+/// The range of passwords, given as `start-end`
+fn get_input(input: &str) -> RangeInclusive<u32> {
+    let mut bounds = input.trim().split('-').map(|bound| bound.parse().unwrap());
+    let start = bounds.next().unwrap();
+    let end = bounds.next().expect("No end of the range given");
+    start..=end
 }
 
 fn is_valid(number: u32) -> bool {
@@ -150,6 +155,11 @@ fn is_valid_with_stricter_criteria(number: u32) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_get_input() {
+        assert_eq!(get_input("264360-746325\n"), 264360..=746325);
+    }
+
     #[test]
     fn test_is_valid_1() {
         let input = 111111;
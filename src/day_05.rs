@@ -167,31 +167,32 @@
 /// What is the diagnostic code for system ID 5?
 use intcode;
 use intcode::Step;
+use solution::{Answer, Solution};
 
-const INPUT: &str = include_str!("../input/day_05.txt");
+pub const INPUT: &str = include_str!("../input/day_05.txt");
 
-pub fn run() {
-    let program = intcode::load(INPUT);
+pub struct Puzzle;
 
-    let air_conditioner_input = 1;
-    let mut runner = intcode::start(program.clone());
-    runner = runner.step(air_conditioner_input);
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let program = intcode::load(input);
 
-    let air_conditioner_diagnostic_code = runner.outputs.last().unwrap();
+        let air_conditioner_input = 1;
+        let mut runner = intcode::start(program).unwrap();
+        runner = runner.step(air_conditioner_input).unwrap();
 
-    println!(
-        "The diagnostic code produced with input 1 is: {}",
-        air_conditioner_diagnostic_code
-    );
+        let air_conditioner_diagnostic_code = runner.outputs.last().unwrap();
+        (*air_conditioner_diagnostic_code).into()
+    }
 
-    let thermal_radiator_input = 5;
-    runner = intcode::start(program);
-    runner = runner.step(thermal_radiator_input);
+    fn part2(&self, input: &str) -> Answer {
+        let program = intcode::load(input);
 
-    let thermal_radiator_diagnostic_code = runner.outputs.last().unwrap();
+        let thermal_radiator_input = 5;
+        let mut runner = intcode::start(program).unwrap();
+        runner = runner.step(thermal_radiator_input).unwrap();
 
-    println!(
-        "The diagnostic code produced with input 5 is: {}",
-        thermal_radiator_diagnostic_code
-    );
+        let thermal_radiator_diagnostic_code = runner.outputs.last().unwrap();
+        (*thermal_radiator_diagnostic_code).into()
+    }
 }
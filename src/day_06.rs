@@ -127,32 +127,31 @@ use petgraph::algo::dijkstra;
 use petgraph::graphmap::GraphMap;
 use petgraph::visit::{Dfs, Reversed};
 use petgraph::{Directed, EdgeType, Undirected};
+use solution::{Answer, Solution};
 type Graph<'a, Ty> = GraphMap<&'a str, (), Ty>;
 
-const INPUT: &str = include_str!("../input/day_06.txt");
+pub const INPUT: &str = include_str!("../input/day_06.txt");
 
-pub fn run() {
-    let orbits = get_input::<Directed>();
+pub struct Puzzle;
 
-    let total = total_orbits(&orbits);
-    println!(
-        "The total number of direct and indirect orbits is: {}",
-        total
-    );
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let orbits = get_input::<Directed>(input);
 
-    let undirected_orbits = get_input::<Undirected>();
+        total_orbits(&orbits).into()
+    }
+
+    fn part2(&self, input: &str) -> Answer {
+        let undirected_orbits = get_input::<Undirected>(input);
 
-    match transfers_needed(&undirected_orbits, "YOU", "SAN") {
-        Some(number) => println!(
-            "The orbital transfers required to get \"YOU\" to \"SAN\" is : {}",
-            number
-        ),
-        None => println!("No connection found between \"YOU\" and \"SAN\""),
-    };
+        transfers_needed(&undirected_orbits, "YOU", "SAN")
+            .expect("No connection found between \"YOU\" and \"SAN\"")
+            .into()
+    }
 }
 
-fn get_input<'a, Ty: EdgeType>() -> Graph<'a, Ty> {
-    let connecting_pairs: Vec<(&str, &str)> = INPUT
+fn get_input<Ty: EdgeType>(input: &str) -> Graph<'_, Ty> {
+    let connecting_pairs: Vec<(&str, &str)> = input
         .lines()
         .map(|line| line.trim().split(')').collect())
         .map(|elements: Vec<&str>| match &elements[..2] {
@@ -135,73 +135,150 @@
 /// Try every combination of the new phase settings on the amplifier feedback
 /// loop. What is the highest signal that can be sent to the thrusters?
 use intcode;
-use intcode::Step;
+use intcode::network::Network;
+use intcode::IntcodeError;
 use itertools::Itertools;
+use solution::{Answer, Solution};
+use std::io::{self, Write};
+use std::iter;
+use std::ops::RangeInclusive;
+use std::thread;
 
-const INPUT: &str = include_str!("../input/day_07.txt");
+pub const INPUT: &str = include_str!("../input/day_07.txt");
+const AMPLIFIERS: usize = 5;
 
-pub fn run() {
-    let amplifier = intcode::load(INPUT);
+/// The phase setting of every amplifier and the signal they send to the
+/// thrusters
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Setting {
+    pub phases: Vec<i64>,
+    pub signal: i64,
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let amplifier = intcode::load(input);
 
-    let no_feedback_highest_signal = max_signal(&amplifier, 0..=4);
-    println!(
-        "The highest signal possible with phase settings 0 through 4 is: {}",
-        no_feedback_highest_signal
-    );
+        let no_feedback = max_signal(&amplifier, AMPLIFIERS, 0..=4).unwrap();
+        no_feedback.unwrap().signal.into()
+    }
+
+    fn part2(&self, input: &str) -> Answer {
+        let amplifier = intcode::load(input);
 
-    let feedback_highest_signal = max_signal(&amplifier, 5..=9);
-    println!(
-        "With phase settings 5 through 9 (feedback) this is: {}",
-        feedback_highest_signal
-    );
+        let feedback = max_signal(&amplifier, AMPLIFIERS, 5..=9).unwrap();
+        feedback.unwrap().signal.into()
+    }
 }
 
-fn max_signal<I>(amplifier: &intcode::Program, possible_settings: I) -> i64
-where
-    I: Itertools<Item = i64>,
-    I::Item: std::clone::Clone,
-{
-    possible_settings
-        .permutations(5)
-        .map(|phase_sequence| run_amplifiers(&amplifier, &phase_sequence))
-        .max()
-        .unwrap()
+fn max_signal(
+    amplifier: &intcode::Program,
+    amplifiers: usize,
+    phases: RangeInclusive<i64>,
+) -> Result<Option<Setting>, IntcodeError> {
+    Ok(highest(&signals(amplifier, amplifiers, phases)?).cloned())
 }
 
-fn run_amplifiers(
+/// The setting with the highest signal, the first one when several are as
+/// high
+pub fn highest(settings: &[Setting]) -> Option<&Setting> {
+    settings.iter().rev().max_by_key(|setting| setting.signal)
+}
+
+/// The signal of every permutation of `amplifiers` different phases, in
+/// order, tried on as many threads as there are cores
+///
+/// Sequences whose last amplifier never sends a signal are left out. The
+/// fault of the earliest sequence that faults is returned as an error.
+pub fn signals(
     amplifier: &intcode::Program,
-    phase_sequence: &Vec<i64>,
-) -> i64 {
-    // initialize phase settings
-    let mut programs = Vec::new();
-    for &phase_setting in phase_sequence {
-        let runner = intcode::start(amplifier.clone());
-        programs.push(runner.step(phase_setting));
+    amplifiers: usize,
+    phases: RangeInclusive<i64>,
+) -> Result<Vec<Setting>, IntcodeError> {
+    let sequences: Vec<_> = phases.permutations(amplifiers).collect();
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = sequences.len().div_ceil(workers).max(1);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = sequences
+            .chunks(chunk)
+            .map(|sequences| {
+                scope.spawn(move || {
+                    let mut settings = Vec::new();
+                    for phases in sequences {
+                        if let Some(signal) = run_amplifiers(amplifier, phases)?
+                        {
+                            let phases = phases.clone();
+                            settings.push(Setting { phases, signal });
+                        }
+                    }
+                    Ok(settings)
+                })
+            })
+            .collect();
+        let mut settings = Vec::new();
+        for worker in workers {
+            settings.extend(worker.join().expect("Amplifier worker panicked")?);
+        }
+        Ok(settings)
+    })
+}
+
+/// A row for every setting, with a column for the phase of every amplifier and
+/// one for the signal
+pub fn write_csv<W: Write>(settings: &[Setting], mut out: W) -> io::Result<()> {
+    let amplifiers = settings.first().map_or(0, |setting| setting.phases.len());
+    let header = (1..=amplifiers)
+        .map(|amplifier| format!("phase_{}", amplifier))
+        .chain(iter::once("signal".to_string()))
+        .join(",");
+    writeln!(out, "{}", header)?;
+    for setting in settings {
+        let phases = setting.phases.iter().join(",");
+        writeln!(out, "{},{}", phases, setting.signal)?;
     }
+    Ok(())
+}
 
-    let mut signal = 0;
-    while programs
+/// The last signal the last amplifier sends, `None` without amplifiers or
+/// when it sends none
+fn run_amplifiers(
+    amplifier: &intcode::Program,
+    phase_sequence: &[i64],
+) -> Result<Option<i64>, IntcodeError> {
+    let mut network = Network::new();
+
+    // initialize phase settings
+    let amplifiers: Vec<_> = phase_sequence
         .iter()
-        .all(|runner| runner.status != intcode::ExitStatus::Finished)
+        .map(|&phase_setting| {
+            let node = network.add_node(amplifier.clone());
+            network.send(node, phase_setting);
+            node
+        })
+        .collect();
+    let (first, last) = match (amplifiers.first(), amplifiers.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return Ok(None),
+    };
+
+    // wire the amplifiers in a loop, the last one feeding back to the first
+    for (&from, &to) in
+        amplifiers.iter().zip(amplifiers.iter().cycle().skip(1))
     {
-        programs = programs
-            .drain(0..)
-            .map(|runner| {
-                let inputs = vec![signal];
-                let new_runner = runner.steps(inputs);
-                signal = new_runner.outputs[0];
-                new_runner
-            })
-            .collect();
+        network.connect(from, to);
     }
+    network.send(first, 0);
 
-    signal
+    let runners = network.run()?;
+    Ok(runners[last].outputs.last().cloned())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
 
     #[test]
     fn test_run_amplifier_sequence_1() {
@@ -210,7 +287,7 @@ mod tests {
         ];
         let phase_sequence = vec![4, 3, 2, 1, 0];
 
-        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), 43210);
+        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), Ok(Some(43210)));
     }
 
     #[test]
@@ -221,7 +298,7 @@ mod tests {
         ];
         let phase_sequence = vec![0, 1, 2, 3, 4];
 
-        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), 54321);
+        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), Ok(Some(54321)));
     }
 
     #[test]
@@ -233,7 +310,7 @@ mod tests {
 
         let phase_sequence = vec![1, 0, 4, 3, 2];
 
-        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), 65210);
+        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), Ok(Some(65210)));
     }
 
     #[test]
@@ -242,7 +319,10 @@ mod tests {
             3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0
         ];
 
-        assert_eq!(max_signal(&amplifier, 0..=4), 43210);
+        assert_eq!(
+            max_signal(&amplifier, 5, 0..=4),
+            Ok(Some(Setting { phases: vec![4, 3, 2, 1, 0], signal: 43210 }))
+        );
     }
 
     #[test]
@@ -252,7 +332,10 @@ mod tests {
             1, 24, 23, 23, 4, 23, 99, 0, 0
         ];
 
-        assert_eq!(max_signal(&amplifier, 0..=4), 54321);
+        assert_eq!(
+            max_signal(&amplifier, 5, 0..=4),
+            Ok(Some(Setting { phases: vec![0, 1, 2, 3, 4], signal: 54321 }))
+        );
     }
 
     #[test]
@@ -262,7 +345,10 @@ mod tests {
             1002, 33, 7, 33, 1, 33, 31, 31, 1, 32, 31, 31, 4, 31, 99, 0, 0, 0
         ];
 
-        assert_eq!(max_signal(&amplifier, 0..=4), 65210);
+        assert_eq!(
+            max_signal(&amplifier, 5, 0..=4),
+            Ok(Some(Setting { phases: vec![1, 0, 4, 3, 2], signal: 65210 }))
+        );
     }
 
     #[test]
@@ -273,7 +359,7 @@ mod tests {
         ];
         let phase_sequence = vec![9, 8, 7, 6, 5];
 
-        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), 139629729);
+        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), Ok(Some(139629729)));
     }
 
     #[test]
@@ -286,7 +372,7 @@ mod tests {
         ];
         let phase_sequence = vec![9, 7, 8, 5, 6];
 
-        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), 18216);
+        assert_eq!(run_amplifiers(&amplifier, &phase_sequence), Ok(Some(18216)));
     }
 
     #[test]
@@ -296,7 +382,10 @@ mod tests {
             27, 1001, 28, -1, 28, 1005, 28, 6, 99, 0, 0, 5
         ];
 
-        assert_eq!(max_signal(&amplifier, 5..=9), 139629729);
+        assert_eq!(
+            max_signal(&amplifier, 5, 5..=9),
+            Ok(Some(Setting { phases: vec![9, 8, 7, 6, 5], signal: 139629729 }))
+        );
     }
 
     #[test]
@@ -308,6 +397,48 @@ mod tests {
             1005, 56, 6, 99, 0, 0, 0, 0, 10
         ];
 
-        assert_eq!(max_signal(&amplifier, 5..=9), 18216);
+        assert_eq!(
+            max_signal(&amplifier, 5, 5..=9),
+            Ok(Some(Setting { phases: vec![9, 7, 8, 5, 6], signal: 18216 }))
+        );
+    }
+
+    #[test]
+    fn test_fewer_amplifiers() {
+        let amplifier = program![
+            3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0
+        ];
+
+        let settings = signals(&amplifier, 2, 0..=2).unwrap();
+
+        assert_eq!(settings.len(), 6);
+        assert_eq!(
+            max_signal(&amplifier, 2, 0..=2),
+            Ok(Some(Setting { phases: vec![2, 1], signal: 21 }))
+        );
+        assert_eq!(max_signal(&amplifier, 4, 0..=2), Ok(None));
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let amplifier = program![
+            3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0
+        ];
+        let mut csv = Vec::new();
+
+        write_csv(&signals(&amplifier, 2, 0..=1).unwrap(), &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "phase_1,phase_2,signal\n0,1,1\n1,0,10\n"
+        );
+    }
+
+    #[test]
+    fn test_amplifiers_without_signal() {
+        assert_eq!(run_amplifiers(&program![3, 0, 99], &[]), Ok(None));
+        assert_eq!(run_amplifiers(&program![99], &[0, 1]), Ok(None));
+        assert_eq!(signals(&program![99], 2, 0..=1), Ok(vec![]));
+        assert!(signals(&program![42], 2, 0..=1).is_err());
     }
 }
@@ -85,46 +85,42 @@
 ///
 /// What message is produced after decoding your image?
 use ndarray::{Array, Array1, Array2, Array3, Axis};
+use solution::{Answer, Solution};
 
-const INPUT: &str = include_str!("../input/day_08.txt");
-
-pub fn run() {
-    let image = load_layers(INPUT, 25, 6);
-
-    // find the layer with the fewest zeroes
-    let (most_zeroes_index, _) = image
-        .outer_iter()
-        .map(|layer| layer.iter().filter(|&&c| c == '0').count())
-        .enumerate()
-        .min_by_key(|&(_, count)| count)
-        .unwrap();
-
-    let most_zeroes_layer = image.slice(s![most_zeroes_index, .., ..]);
-    let ones = most_zeroes_layer.iter().filter(|&&c| c == '1').count();
-    let twos = most_zeroes_layer.iter().filter(|&&c| c == '2').count();
-
-    println!(
-        "On the layer with the fewest 0 digits, \
-         the number of 1 digits ({}) multiplied by \
-         the number of 2 digits ({}) is: {}",
-        ones,
-        twos,
-        ones * twos
-    );
-
-    // decode the image
-    let decoded = decode(image);
-
-    // display the image
-    println!("The decoded image looks like:");
-    for row in decoded.genrows() {
-        for pixel in row.iter() {
-            match pixel {
-                '1' => print!("\u{2588}"),
-                _ => print!(" "),
-            }
-        }
-        print!("\n");
+pub const INPUT: &str = include_str!("../input/day_08.txt");
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let image = load_layers(input, 25, 6);
+
+        // find the layer with the fewest zeroes
+        let (most_zeroes_index, _) = image
+            .outer_iter()
+            .map(|layer| layer.iter().filter(|&&c| c == '0').count())
+            .enumerate()
+            .min_by_key(|&(_, count)| count)
+            .unwrap();
+
+        let most_zeroes_layer = image.slice(s![most_zeroes_index, .., ..]);
+        let ones = most_zeroes_layer.iter().filter(|&&c| c == '1').count();
+        let twos = most_zeroes_layer.iter().filter(|&&c| c == '2').count();
+        (ones * twos).into()
+    }
+
+    fn part2(&self, input: &str) -> Answer {
+        let image = load_layers(input, 25, 6);
+
+        // decode the image
+        let decoded = decode(image);
+
+        // white pixels are lit
+        Answer::image(
+            decoded.genrows().into_iter().map(|row| {
+                row.into_iter().map(|&pixel| pixel == '1').collect::<Vec<_>>()
+            }),
+        )
     }
 }
 
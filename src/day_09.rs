@@ -82,25 +82,26 @@
 /// distress signal?
 use intcode;
 use intcode::Step;
+use solution::{Answer, Solution};
 
-const INPUT: &str = include_str!("../input/day_09.txt");
+pub const INPUT: &str = include_str!("../input/day_09.txt");
 
-pub fn run() {
-    let boost_program = intcode::load(INPUT);
-    let mut test_runner = intcode::start(boost_program.clone());
-    test_runner = test_runner.step(1);
-    let keycode = test_runner.outputs[0];
+pub struct Puzzle;
 
-    println!(
-        "Running the BOOST program in test produces the keycode: {}",
-        keycode
-    );
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let boost_program = intcode::load(input);
+        let mut test_runner = intcode::start(boost_program).unwrap();
+        test_runner = test_runner.step(1).unwrap();
+        let keycode = test_runner.outputs[0];
+        keycode.into()
+    }
 
-    let mut runner = intcode::start(boost_program.clone());
-    runner = runner.step(2);
-    let coordinates = runner.outputs[0];
-    println!(
-        "In sensor boost mode it produces the coordinates: {}",
-        coordinates
-    );
+    fn part2(&self, input: &str) -> Answer {
+        let boost_program = intcode::load(input);
+        let mut runner = intcode::start(boost_program).unwrap();
+        runner = runner.step(2).unwrap();
+        let coordinates = runner.outputs[0];
+        coordinates.into()
+    }
 }
@@ -213,11 +213,12 @@
 /// coordinate? (For example, 8,2 becomes 802.)
 use itertools::Itertools;
 use num::integer::gcd;
+use solution::{Answer, Solution};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
-const INPUT: &str = include_str!("../input/day_10.txt");
+pub const INPUT: &str = include_str!("../input/day_10.txt");
 
 type Point = (i32, i32);
 
@@ -335,52 +336,65 @@ impl Hash for Fraction {
     }
 }
 
-pub fn run() {
-    let asteroids = load_asteroids(INPUT);
+pub struct Puzzle;
 
-    let fractions_map = calculate_fractions(&asteroids);
-    let (station_asteroids, maximum_visible) = fractions_map
-        .values()
-        .map(|fractions| (fractions, fractions.into_iter().dedup().count()))
-        .max_by_key(|&(_, visible)| visible)
-        .unwrap();
-    println!(
-        "The amount of asteroids to be detected from the best location is: {}",
-        maximum_visible
-    );
-
-    let mut asteroids_left: Vec<&Fraction> = station_asteroids.iter().collect();
-    let mut vaporized_asteroids = 0;
-    'vaporize: while !asteroids_left.is_empty() {
-        let mut same_fraction_asteroids = Vec::new();
-        let mut last_fraction = None;
-        for asteroid_fraction in asteroids_left.iter() {
-            let new_fraction = Some(asteroid_fraction);
-            if new_fraction == last_fraction {
-                // cannot be vaporized, was behind another asteroid
-                same_fraction_asteroids.push(*asteroid_fraction);
-                continue;
-            } else {
-                // vaporize
-                vaporized_asteroids += 1;
-                last_fraction = new_fraction;
-            }
-            if vaporized_asteroids == 200 {
-                // Found our target
-                let asteroid = asteroid_fraction.destination;
-                let (x, y) = asteroid;
-                let location = x * 100 + y;
-                println!(
-                    "Asteroid at {} is the 200th to be vaporized",
-                    location
-                );
-                break 'vaporize;
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let asteroids = load_asteroids(input);
+
+        let fractions_map = calculate_fractions(&asteroids);
+        let (_, maximum_visible) = best_location(&fractions_map);
+        maximum_visible.into()
+    }
+
+    fn part2(&self, input: &str) -> Answer {
+        let asteroids = load_asteroids(input);
+
+        let fractions_map = calculate_fractions(&asteroids);
+        let (station_asteroids, _) = best_location(&fractions_map);
+
+        let mut asteroids_left: Vec<&Fraction> =
+            station_asteroids.iter().collect();
+        let mut vaporized_asteroids = 0;
+        while !asteroids_left.is_empty() {
+            let mut same_fraction_asteroids = Vec::new();
+            let mut last_fraction = None;
+            for asteroid_fraction in asteroids_left.iter() {
+                let new_fraction = Some(asteroid_fraction);
+                if new_fraction == last_fraction {
+                    // cannot be vaporized, was behind another asteroid
+                    same_fraction_asteroids.push(*asteroid_fraction);
+                    continue;
+                } else {
+                    // vaporize
+                    vaporized_asteroids += 1;
+                    last_fraction = new_fraction;
+                }
+                if vaporized_asteroids == 200 {
+                    // Found our target
+                    let asteroid = asteroid_fraction.destination;
+                    let (x, y) = asteroid;
+                    return (x * 100 + y).into();
+                }
             }
+            asteroids_left = same_fraction_asteroids;
         }
-        asteroids_left = same_fraction_asteroids;
+        panic!("Less than 200 asteroids can be vaporized")
     }
 }
 
+/// The fractions of the asteroids seen from the location that detects the
+/// most of them, with the amount it detects
+fn best_location(
+    fractions_map: &HashMap<Point, Vec<Fraction>>,
+) -> (&Vec<Fraction>, usize) {
+    fractions_map
+        .values()
+        .map(|fractions| (fractions, fractions.iter().dedup().count()))
+        .max_by_key(|&(_, visible)| visible)
+        .unwrap()
+}
+
 fn calculate_fractions(
     asteroids: &Vec<Point>,
 ) -> HashMap<Point, Vec<Fraction>> {
@@ -98,13 +98,12 @@
 /// Build a new emergency hull painting robot and run the Intcode program on it.
 /// How many panels does it paint at least once?
 use intcode;
-use intcode::Step;
-use itertools::Itertools;
 use num;
 use num_derive::{FromPrimitive, ToPrimitive};
+use solution::{Answer, Solution};
 use std::collections::HashMap;
 
-const INPUT: &str = include_str!("../input/day_11.txt");
+pub const INPUT: &str = include_str!("../input/day_11.txt");
 
 type Point = (i32, i32);
 type Hull = HashMap<Point, i64>;
@@ -164,66 +163,57 @@ impl Robot {
     }
 }
 
-pub fn run() {
-    let brain = intcode::load(INPUT);
+pub struct Puzzle;
 
-    let painted_hull = paint_hull(brain.clone(), HashMap::new());
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let brain = intcode::load(input);
 
-    println!(
-        "The amount of panels painted at least once is: {}",
-        painted_hull.len()
-    );
-    display(&painted_hull);
+        let painted_hull = paint_hull(brain, HashMap::new());
+        painted_hull.len().into()
+    }
 
-    // start on a white square
-    let mut white_starting_hull = HashMap::new();
-    white_starting_hull.insert((0, 0), 1);
-    let proper_painted_hull = paint_hull(brain.clone(), white_starting_hull);
+    fn part2(&self, input: &str) -> Answer {
+        let brain = intcode::load(input);
 
-    println!("With starting on a white square the robot paints:");
-    display(&proper_painted_hull);
+        // start on a white square
+        let mut white_starting_hull = HashMap::new();
+        white_starting_hull.insert((0, 0), 1);
+        let proper_painted_hull = paint_hull(brain, white_starting_hull);
+        render(&proper_painted_hull)
+    }
 }
 
 fn paint_hull(brain: intcode::Program, mut hull: Hull) -> Hull {
     let mut robot = Robot::new();
-    let mut runner = intcode::start(brain);
+    let mut runner = intcode::Runner::new(brain);
 
     loop {
-        // execute paint instructions
-        for (paint_instruction, turn_instruction) in
-            runner.outputs.iter().tuples()
-        {
-            robot.paint(paint_instruction, &mut hull);
-            robot.turn(turn_instruction);
-        }
-        // read the camera
-        let input = robot.read_camera(&hull);
-        // start a next step
-        runner = runner.step(input);
-
-        if runner.status == intcode::ExitStatus::Finished {
-            break;
+        // the brain reads the camera, then tells what to paint and where to
+        // turn to
+        let mut camera = || Some(robot.read_camera(&hull));
+        runner = runner
+            .run_until_outputs(&mut camera, 2)
+            .and_then(intcode::Runner::or_fault)
+            .unwrap();
+        if runner.state.status == intcode::ExitStatus::Halted {
+            return hull;
         }
+        robot.paint(&runner.outputs[0], &mut hull);
+        robot.turn(&runner.outputs[1]);
     }
-    hull
 }
 
-fn display(hull: &Hull) {
+/// The painted panels, white ones are lit
+fn render(hull: &Hull) -> Answer {
     let x_min = hull.keys().map(|&(x, _)| x).min().unwrap();
     let x_max = hull.keys().map(|&(x, _)| x).max().unwrap();
     let y_min = hull.keys().map(|&(_, y)| y).min().unwrap();
     let y_max = hull.keys().map(|&(_, y)| y).max().unwrap();
 
-    for y in y_min..=y_max {
-        for x in x_min..=x_max {
-            match *hull.get(&(x, y)).unwrap_or(&0) {
-                0 => print!(" "),
-                1 => print!("\u{2588}"),
-                _ => print!("?"),
-            };
-        }
-        print!("\n");
-    }
+    Answer::image((y_min..=y_max).map(|y| {
+        (x_min..=x_max).map(move |x| *hull.get(&(x, y)).unwrap_or(&0) == 1)
+    }))
 }
 
 #[cfg(test)]
@@ -210,10 +210,11 @@
 use itertools::Itertools;
 use num::integer::lcm;
 use regex::Regex;
+use solution::{Answer, Solution};
 use std::cmp::Ordering;
 use std::ops::AddAssign;
 
-const INPUT: &str = include_str!("../input/day_12.txt");
+pub const INPUT: &str = include_str!("../input/day_12.txt");
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 struct Vector3D {
@@ -266,49 +267,46 @@ impl Moon {
     }
 }
 
-pub fn run() {
-    let initial_moons = load_moons(INPUT);
-    let mut moons = initial_moons.clone();
+pub struct Puzzle;
 
-    for _ in 0..1000 {
-        step(&mut moons, vec!['x', 'y', 'z']);
-    }
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let mut moons = load_moons(input);
 
-    let total_system_energy: i32 =
-        moons.iter().map(|moon| moon.total_energy()).sum();
-
-    println!(
-        "The total energy of the system after 1000 steps is: {}",
-        total_system_energy
-    );
-
-    // reset to initial state
-    moons = initial_moons.clone();
-
-    let mut periodic_orbits = Vec::new();
-    // split out finding periodic orbits over the 3 axes
-    for &axis in ['x', 'y', 'z'].iter() {
-        let mut steps: u64 = 0;
-        loop {
-            step(&mut moons, vec![axis]);
-            steps += 1;
-            // only compare to the initial state
-            if moons == initial_moons {
-                break;
-            }
+        for _ in 0..1000 {
+            step(&mut moons, vec!['x', 'y', 'z']);
         }
-        periodic_orbits.push(steps);
+
+        let total_system_energy: i32 =
+            moons.iter().map(|moon| moon.total_energy()).sum();
+        total_system_energy.into()
     }
 
-    // now find the Lowest Common Multiple of the 3 found orbits
-    let combined_periodic_orbit = periodic_orbits
-        .iter()
-        .fold(1, |combined, periodic_orbit| lcm(combined, *periodic_orbit));
+    fn part2(&self, input: &str) -> Answer {
+        let initial_moons = load_moons(input);
+        let mut moons = initial_moons.clone();
+
+        let mut periodic_orbits = Vec::new();
+        // split out finding periodic orbits over the 3 axes
+        for &axis in ['x', 'y', 'z'].iter() {
+            let mut steps: u64 = 0;
+            loop {
+                step(&mut moons, vec![axis]);
+                steps += 1;
+                // only compare to the initial state
+                if moons == initial_moons {
+                    break;
+                }
+            }
+            periodic_orbits.push(steps);
+        }
 
-    println!(
-        "It took {} steps to get back to the initial state",
-        combined_periodic_orbit
-    )
+        // now find the Lowest Common Multiple of the 3 found orbits
+        let combined_periodic_orbit = periodic_orbits
+            .iter()
+            .fold(1, |combined, periodic_orbit| lcm(combined, *periodic_orbit));
+        combined_periodic_orbit.into()
+    }
 }
 
 fn step(moons: &mut Vec<Moon>, axes: Vec<char>) {
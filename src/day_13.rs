@@ -49,77 +49,61 @@
 ///
 /// Beat the game by breaking all the blocks. What is your score after the last
 /// block is broken?
-use console::Term;
 use intcode;
-use intcode::Step;
 use itertools::Itertools;
 use num::FromPrimitive;
 use num_derive::{FromPrimitive, ToPrimitive};
+use solution::{Answer, Solution};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
-const INPUT: &str = include_str!("../input/day_13.txt");
-static mut DISPLAY_SETTINGS: DisplaySettings = DisplaySettings {
-    initialized: false,
-    x_min: 0,
-    x_max: 0,
-    y_min: 0,
-    y_max: 0,
-    size: 0,
-};
+pub const INPUT: &str = include_str!("../input/day_13.txt");
 
-pub fn run() {
-    let mut game = intcode::load(INPUT);
-    let mut runner = intcode::start(game.clone());
-    let mut screen = Screen::new();
-    render(&runner.outputs, &mut screen);
+pub struct Puzzle;
 
-    let block_tiles =
-        screen.values().filter(|tile| tile == &&Tile::Block).count();
-    println!(
-        "There have been {} block tiles rendered on the screen",
-        block_tiles
-    );
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let game = intcode::load(input);
+        let runner = intcode::start(game).unwrap();
+        let mut screen = Screen::new();
+        render(&runner.outputs, &mut screen);
+
+        let block_tiles =
+            screen.values().filter(|tile| tile == &&Tile::Block).count();
+        block_tiles.into()
+    }
 
-    // set the game to free play
-    game.insert(0, 2);
+    fn part2(&self, input: &str) -> Answer {
+        let mut game = intcode::load(input);
 
-    // play the game
-    screen = Screen::new();
-    runner = intcode::start(game);
-    let mut score = render(&runner.outputs, &mut screen).unwrap();
-    loop {
-        let joystick = determine_joystick(&screen);
-        runner = runner.step(joystick as i64);
-        match render(&runner.outputs, &mut screen) {
-            Some(updated_score) => {
+        // set the game to free play
+        game.set(0, 2);
+
+        // play the game, handling every tile as soon as it is drawn
+        let mut screen = Screen::new();
+        let mut runner = intcode::Runner::new(game);
+        let mut score = 0;
+        loop {
+            let mut joystick = || Some(determine_joystick(&screen) as i64);
+            runner = runner
+                .run_until_outputs(&mut joystick, 3)
+                .and_then(intcode::Runner::or_fault)
+                .unwrap();
+            if let Some(updated_score) = render(&runner.outputs, &mut screen) {
                 score = updated_score;
             }
-            None => {}
-        };
-
-        // comment the display for faster execution
-        display(&screen, score);
 
-        if runner.status == intcode::ExitStatus::Finished {
-            break;
+            if runner.state.status == intcode::ExitStatus::Halted {
+                break;
+            }
         }
+        score.into()
     }
-    println!("The final score of the game is: {}", score);
 }
 
 type Point = (i64, i64);
 type Screen = HashMap<Point, Tile>;
 
-struct DisplaySettings {
-    initialized: bool,
-    x_min: i64,
-    x_max: i64,
-    y_min: i64,
-    y_max: i64,
-    size: usize,
-}
-
 #[derive(Debug, PartialEq, FromPrimitive)]
 enum Tile {
     Empty = 0,
@@ -155,48 +139,6 @@ fn find_tile(screen: &Screen, to_find: Tile) -> Option<Point> {
     }
 }
 
-fn init_display_settings(screen: &Screen) {
-    unsafe {
-        DISPLAY_SETTINGS.x_min = screen.keys().map(|&(x, _)| x).min().unwrap();
-        DISPLAY_SETTINGS.x_max = screen.keys().map(|&(x, _)| x).max().unwrap();
-        DISPLAY_SETTINGS.y_min = screen.keys().map(|&(_, y)| y).min().unwrap();
-        DISPLAY_SETTINGS.y_max = screen.keys().map(|&(_, y)| y).max().unwrap();
-        DISPLAY_SETTINGS.size =
-            (DISPLAY_SETTINGS.y_max - DISPLAY_SETTINGS.y_min) as usize + 2;
-        DISPLAY_SETTINGS.initialized = true;
-
-        // move the cursor down to create space for the display
-        for _ in 0..DISPLAY_SETTINGS.size {
-            print!("\n");
-        }
-    }
-}
-
-fn display(screen: &Screen, score: i64) {
-    unsafe {
-        if !DISPLAY_SETTINGS.initialized {
-            init_display_settings(screen);
-        }
-
-        // move the cursor back up to overwrite the existing display
-        Term::stdout().move_cursor_up(DISPLAY_SETTINGS.size).ok();
-
-        for y in DISPLAY_SETTINGS.y_min..=DISPLAY_SETTINGS.y_max {
-            for x in DISPLAY_SETTINGS.x_min..=DISPLAY_SETTINGS.x_max {
-                match *screen.get(&(x, y)).unwrap_or(&Tile::Empty) {
-                    Tile::Empty => print!(" "),
-                    Tile::Wall => print!("\u{2588}"),
-                    Tile::Block => print!("\u{2592}"),
-                    Tile::HorizontalPaddle => print!("-"),
-                    Tile::Ball => print!("o"),
-                };
-            }
-            print!("\n");
-        }
-        println!("Score: {:09}", score);
-    }
-}
-
 fn render(outputs: &intcode::Outputs, screen: &mut Screen) -> Option<i64> {
     let mut score = None;
     for (&x, &y, &value) in outputs.iter().tuples() {
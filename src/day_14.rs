@@ -126,52 +126,52 @@
 /// Given 1 trillion ORE, what is the maximum amount of FUEL you can produce?
 use itertools::Itertools;
 use num::Integer;
+use solution::{Answer, Solution};
 use std::collections::HashMap;
 
-const INPUT: &str = include_str!("../input/day_14.txt");
+pub const INPUT: &str = include_str!("../input/day_14.txt");
 
-pub fn run() {
-    let formulas = load_formulas(INPUT);
-    let mut storage = Storage::new();
-
-    // force to produce 1 FUEL
-    storage.insert("FUEL", -1);
-
-    storage = produce_until_none_missing(storage, &formulas);
+pub struct Puzzle;
 
-    let ore_per_fuel = -storage.get(&"ORE").expect("the ORE entry to be there");
-    println!("The amount of ORE needed to produce 1 FUEL is: {}", ore_per_fuel);
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let formulas = load_formulas(input);
 
-    // set the amount of ore available to a trillion
-    let ore_available = 1_000_000_000_000;
-    storage = Storage::new();
-    storage.insert("ORE", ore_available);
-
-    let mut fuel_produced = 0;
+        ore_per_fuel(&formulas).into()
+    }
 
-    // execute fuel production in batches
-    let mut next_fuel_batch;
-    loop {
-        // set a batch size that will at least be reached
-        next_fuel_batch = storage.get("ORE").unwrap() / ore_per_fuel;
-        // make sure the do at least 1
-        if next_fuel_batch == 0 {
-            next_fuel_batch = 1;
-        }
-        *storage.entry("FUEL").or_insert(0) -= next_fuel_batch;
+    fn part2(&self, input: &str) -> Answer {
+        let formulas = load_formulas(input);
+        let ore_per_fuel = ore_per_fuel(&formulas);
 
-        // produce the batch
-        storage = produce_until_none_missing(storage, &formulas);
-        if storage.get("ORE").expect("the ORE entry to be there") < &0 {
-            // stop if it goes beyond the capacity of ORE
-            break;
+        // set the amount of ore available to a trillion
+        let ore_available = 1_000_000_000_000;
+        let mut storage = Storage::new();
+        storage.insert("ORE", ore_available);
+
+        let mut fuel_produced = 0;
+
+        // execute fuel production in batches
+        let mut next_fuel_batch;
+        loop {
+            // set a batch size that will at least be reached
+            next_fuel_batch = storage.get("ORE").unwrap() / ore_per_fuel;
+            // make sure the do at least 1
+            if next_fuel_batch == 0 {
+                next_fuel_batch = 1;
+            }
+            *storage.entry("FUEL").or_insert(0) -= next_fuel_batch;
+
+            // produce the batch
+            storage = produce_until_none_missing(storage, &formulas);
+            if storage.get("ORE").expect("the ORE entry to be there") < &0 {
+                // stop if it goes beyond the capacity of ORE
+                break;
+            }
+            fuel_produced += next_fuel_batch;
         }
-        fuel_produced += next_fuel_batch;
+        fuel_produced.into()
     }
-    println!(
-        "The amount of FUEL that can be produced with a trillion ORE is: {}",
-        fuel_produced
-    );
 }
 
 type Storage<'a> = HashMap<&'a str, i64>;
@@ -228,6 +228,17 @@ impl<'a> Component<'a> {
     }
 }
 
+fn ore_per_fuel(formulas: &Vec<Formula>) -> i64 {
+    let mut storage = Storage::new();
+
+    // force to produce 1 FUEL
+    storage.insert("FUEL", -1);
+
+    storage = produce_until_none_missing(storage, formulas);
+
+    -storage.get(&"ORE").expect("the ORE entry to be there")
+}
+
 fn produce_until_none_missing<'a>(
     mut storage: Storage<'a>,
     formulas: &'a Vec<Formula<'a>>,
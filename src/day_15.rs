@@ -156,41 +156,34 @@ use num::FromPrimitive;
 use num_derive::{FromPrimitive, ToPrimitive};
 use petgraph::algo::dijkstra;
 use petgraph::graphmap::UnGraphMap;
+use solution::{Answer, Solution};
 use std::collections::HashMap;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-const INPUT: &str = include_str!("../input/day_15.txt");
+pub const INPUT: &str = include_str!("../input/day_15.txt");
 
-pub fn run() {
-    let program = intcode::load(INPUT);
+pub struct Puzzle;
 
-    let map = create_map(program);
-    display(&map);
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        let map = create_map(intcode::load(input));
+        let distance_map = distances_from_oxygen_system(&map);
 
-    // converting to a graph for easier traversal
-    let graph = convert_to_graph(&map);
-
-    // create a map with distances to all points
-    let oxygen_system_location = find_location(Section::OxygenSystem, &map);
-    let distance_map = dijkstra(&graph, oxygen_system_location, None, |_| 1);
-
-    // find the distance to the starting point
-    let starting_location = find_location(Section::Start, &map);
-    let distance_to_start = distance_map.get(&starting_location).unwrap();
-
-    println!(
-        "The distance from the starting point to the oxygen system is: {}",
-        distance_to_start
-    );
-
-    // find the longest distance from the oxygen system
-    let longest_distance =
-        distance_map.iter().map(|(_, distance)| distance).max().unwrap();
-    println!(
-        "The amount of minutes it will take to fill everything with oxygen is: {}",
-        longest_distance
-    );
+        // find the distance to the starting point
+        let starting_location = find_location(Section::Start, &map);
+        let distance_to_start = distance_map.get(&starting_location).unwrap();
+        (*distance_to_start).into()
+    }
+
+    fn part2(&self, input: &str) -> Answer {
+        let map = create_map(intcode::load(input));
+        let distance_map = distances_from_oxygen_system(&map);
+
+        // find the longest distance from the oxygen system
+        let longest_distance = distance_map.values().max().unwrap();
+        (*longest_distance).into()
+    }
 }
 
 fn find_location(section: Section, map: &Map) -> Point {
@@ -207,31 +200,11 @@ fn find_location(section: Section, map: &Map) -> Point {
         .unwrap()
 }
 
-fn display(map: &Map) {
-    let x_min = map.keys().map(|&(x, _)| x).min().unwrap();
-    let x_max = map.keys().map(|&(x, _)| x).max().unwrap();
-    let y_min = map.keys().map(|&(_, y)| y).min().unwrap();
-    let y_max = map.keys().map(|&(_, y)| y).max().unwrap();
-
-    for y in y_min..=y_max {
-        for x in x_min..=x_max {
-            match map.get(&(x, y)) {
-                None => print!(" "),
-                Some(Section::Path) => print!("\u{2591}"),
-                Some(Section::Wall) => print!("\u{2588}"),
-                Some(Section::OxygenSystem) => print!("O"),
-                Some(Section::Start) => print!("X"),
-            };
-        }
-        print!("\n");
-    }
-}
-
 fn create_map(program: intcode::Program) -> Map {
     let mut droids = Vec::new();
     let mut map = Map::new();
 
-    let starting_droid = Droid::new(intcode::start(program));
+    let starting_droid = Droid::new(intcode::start(program).unwrap());
     map.insert(starting_droid.position.clone(), Section::Start);
     droids.push(starting_droid);
 
@@ -260,6 +233,15 @@ fn create_map(program: intcode::Program) -> Map {
     map
 }
 
+/// The distance of every point on the map to the oxygen system
+fn distances_from_oxygen_system(map: &Map) -> HashMap<Point, i32> {
+    // converting to a graph for easier traversal
+    let graph = convert_to_graph(map);
+
+    let oxygen_system_location = find_location(Section::OxygenSystem, map);
+    dijkstra(&graph, oxygen_system_location, None, |_| 1).into_iter().collect()
+}
+
 fn convert_to_graph(map: &Map) -> Graph {
     let mut graph = Graph::new();
 
@@ -327,7 +309,7 @@ impl<S: intcode::Step> Droid<S> {
     }
 
     fn update_map(mut self, command: Direction, map: &mut Map) -> Self {
-        self.stepper = self.stepper.step(command as i64);
+        self.stepper = self.stepper.step(command as i64).unwrap();
         let next_position = self.point_in_direction(command);
 
         let reply = FromPrimitive::from_i64(self.stepper.output());
@@ -374,7 +356,7 @@ mod tests {
         let mut step_1 = MockStep::new();
 
         step_1.expect_output().return_const(Reply::Wall as i64);
-        step_0.expect_step().return_once(move |_| step_1);
+        step_0.expect_step().return_once(move |_| Ok(step_1));
 
         let mut map = Map::new();
         let mut droid = Droid::new(step_0);
@@ -393,7 +375,7 @@ mod tests {
         let mut step_1 = MockStep::new();
 
         step_1.expect_output().return_const(Reply::Moved as i64);
-        step_0.expect_step().return_once(move |_| step_1);
+        step_0.expect_step().return_once(move |_| Ok(step_1));
 
         let mut map = Map::new();
         let mut droid = Droid::new(step_0);
@@ -417,9 +399,9 @@ mod tests {
         step_2.expect_output().return_const(Reply::Moved as i64);
         step_1.expect_output().return_const(Reply::Moved as i64);
 
-        step_2.expect_step().return_once(move |_| step_3);
-        step_1.expect_step().return_once(move |_| step_2);
-        step_0.expect_step().return_once(move |_| step_1);
+        step_2.expect_step().return_once(move |_| Ok(step_3));
+        step_1.expect_step().return_once(move |_| Ok(step_2));
+        step_0.expect_step().return_once(move |_| Ok(step_1));
 
         let mut droid = Droid::new(step_0);
         let mut map = Map::new();
@@ -117,51 +117,52 @@
 ///
 /// After repeating your input signal 10000 times and running 100 phases of FFT, what is the
 /// eight-digit message embedded in the final output list?
+use solution::{Answer, Solution};
 use std::iter;
 
-const INPUT: &str = include_str!("../input/day_16.txt");
+pub const INPUT: &str = include_str!("../input/day_16.txt");
 
-pub fn run() {
-    let original_signal = load_signal(INPUT);
+pub struct Puzzle;
 
-    // run a 100 phases of FFT on the signal
-    let mut signal = original_signal.clone();
-    for _ in 0..100 {
-        signal = execute_phase(signal);
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Answer {
+        // run a 100 phases of FFT on the signal
+        let mut signal = load_signal(input);
+        for _ in 0..100 {
+            signal = execute_phase(signal);
+        }
+
+        // take the first eight digits
+        let first_8 =
+            signal.iter().take(8).map(|i| i.to_string()).collect::<String>();
+        first_8.into()
     }
 
-    // take the first eight digits
-    let first_8 =
-        signal.iter().take(8).map(|i| i.to_string()).collect::<String>();
-    println!(
-        "The first eight digits of the final output list are: {}",
-        first_8
-    );
+    fn part2(&self, input: &str) -> Answer {
+        let original_signal = load_signal(input);
 
-    // reset the signal
-    let mut extended_signal = original_signal
-        .iter()
-        .cycle()
-        .take(10_000 * signal.len())
-        .cloned()
-        .collect();
-
-    // take the offset
-    let offset = get_offset(&extended_signal);
-    // run a 100 phases of the FFT with the shortened algorithm
-    for _ in 0..100 {
-        extended_signal = execute_phase_only_from(extended_signal, offset);
+        // repeat the signal
+        let mut extended_signal = original_signal
+            .iter()
+            .cycle()
+            .take(10_000 * original_signal.len())
+            .cloned()
+            .collect();
+
+        // take the offset
+        let offset = get_offset(&extended_signal);
+        // run a 100 phases of the FFT with the shortened algorithm
+        for _ in 0..100 {
+            extended_signal = execute_phase_only_from(extended_signal, offset);
+        }
+        let offsetted_8 = extended_signal
+            .iter()
+            .skip(offset)
+            .take(8)
+            .map(|i| i.to_string())
+            .collect::<String>();
+        offsetted_8.into()
     }
-    let offsetted_8 = extended_signal
-        .iter()
-        .skip(offset)
-        .take(8)
-        .map(|i| i.to_string())
-        .collect::<String>();
-    println!(
-        "With the real signal the offsetted eight digits of the final output list are: {}",
-        offsetted_8
-    );
 }
 
 type Signal = Vec<i32>;
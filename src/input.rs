@@ -0,0 +1,117 @@
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Environment variable naming a directory of inputs, used when no input is
+/// given on the command line
+pub const INPUT_DIR: &str = "AOC_INPUT_DIR";
+
+/// Where the input of a day comes from
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Provider {
+    /// The input the solutions were written for, part of the binary
+    Embedded,
+    /// A single file, for a single day
+    File(String),
+    /// A directory holding `day_NN.txt` for every day
+    Directory(String),
+    /// Standard input, for a single day
+    Stdin,
+}
+
+impl Provider {
+    /// The directory in `AOC_INPUT_DIR` when it is set, embedded otherwise
+    pub fn from_env() -> Provider {
+        match env::var(INPUT_DIR) {
+            Ok(directory) if !directory.is_empty() => {
+                Provider::Directory(directory)
+            }
+            _ => Provider::Embedded,
+        }
+    }
+
+    /// Whether the provider can give a different input to every day
+    pub fn per_day(&self) -> bool {
+        matches!(self, Provider::Embedded | Provider::Directory(_))
+    }
+
+    /// The input of `day`, `embedded` is the one compiled into the binary
+    pub fn read(
+        &self,
+        day: &str,
+        embedded: &'static str,
+    ) -> Result<String, InputError> {
+        let path = match self {
+            Provider::Embedded => return Ok(embedded.to_string()),
+            Provider::Stdin => {
+                let mut text = String::new();
+                return match io::stdin().read_to_string(&mut text) {
+                    Ok(_) => Ok(text),
+                    Err(error) => Err(InputError::new("stdin", error)),
+                };
+            }
+            Provider::File(path) => path.clone(),
+            Provider::Directory(directory) => Path::new(directory)
+                .join(format!("{}.txt", day))
+                .to_string_lossy()
+                .into_owned(),
+        };
+        fs::read_to_string(&path).map_err(|error| InputError::new(&path, error))
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    /// The file, or stdin, that could not be read
+    pub path: String,
+    pub error: io::Error,
+}
+
+impl InputError {
+    fn new(path: &str, error: io::Error) -> InputError {
+        InputError { path: path.to_string(), error }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "could not read {}: {}", self.path, self.error)
+    }
+}
+
+impl error::Error for InputError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded() {
+        let input = Provider::Embedded.read("day_01", "12\n14\n").unwrap();
+        assert_eq!(input, "12\n14\n");
+    }
+
+    #[test]
+    fn test_directory() {
+        let directory = env::temp_dir().join("aoc_input_provider_test");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("day_04.txt"), "111111-111122\n").unwrap();
+        let provider =
+            Provider::Directory(directory.to_string_lossy().into_owned());
+
+        assert_eq!(provider.read("day_04", "").unwrap(), "111111-111122\n");
+        let error = provider.read("day_05", "").unwrap_err();
+        assert!(error.path.ends_with("day_05.txt"), "{}", error);
+        assert_eq!(error.error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let provider = Provider::File("no/such/input.txt".to_string());
+        let error = provider.read("day_01", "12").unwrap_err();
+        assert!(error.to_string().starts_with("could not read no/such/input"));
+    }
+}
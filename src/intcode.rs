@@ -1,49 +1,219 @@
 #[cfg(test)]
 use mockall::automock;
-use std::collections::HashMap;
+use std::error;
+use std::fmt;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg(test)]
+macro_rules! program {
+    ( $( $x:expr ),* ) => {
+        ::intcode::Program::from(vec![$( $x ),*])
+    };
+}
+
+pub mod ascii;
+pub mod asm;
+mod cache;
+pub mod cell;
+pub mod debugger;
+pub mod disasm;
+#[cfg(test)]
+mod fuzz;
+pub mod io;
+pub mod isa;
+pub mod limits;
+mod memory;
+pub mod network;
+pub mod snapshot;
+pub mod sweep;
+pub mod symbolic;
+pub mod trace;
+
+pub use self::cell::Cell;
+pub use self::io::{Sink, Source};
+pub use self::isa::InstructionSet;
+pub use self::limits::{Limit, Limits};
+pub use self::memory::Memory;
+pub use self::trace::Tracer;
+
+use self::cache::{Cache, Decoded};
+use self::isa::{Context, Flow};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 enum Mode {
     Position,
     Immediate,
     Relative,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+/// Why a program stopped running
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ExitStatus {
-    Finished,
-    WaitingForInput(i64, i64),
+    /// The next instruction needs an input that is not available yet
+    WaitingForInput,
+    /// An output was produced and the caller asked to pause after it
+    OutputReady,
+    /// The program executed its halt instruction
+    Halted,
+    /// An instruction could not be executed
+    Faulted(IntcodeError),
+    /// The run was stopped by one of its limits before the next instruction
+    LimitReached(Limit),
+}
+
+/// Where a machine is in its program
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MachineState {
+    /// Address of the next instruction to execute
+    pub ip: i64,
+    pub relative_base: i64,
+    /// Amount of instructions executed so far
+    pub cycles: u64,
+    pub status: ExitStatus,
+}
+
+impl MachineState {
+    /// The state of a machine that has not executed anything yet
+    pub fn new() -> MachineState {
+        MachineState {
+            ip: 0,
+            relative_base: 0,
+            cycles: 0,
+            status: ExitStatus::WaitingForInput,
+        }
+    }
+}
+
+impl Default for MachineState {
+    fn default() -> MachineState {
+        MachineState::new()
+    }
+}
+
+/// Reasons for an Intcode program to stop abnormally
+///
+/// Faults raised while executing an instruction carry the instruction pointer
+/// (`position`) and the relative base of the machine at that moment. `Trap` is
+/// raised by instructions of custom instruction sets, `Overflow` when a result
+/// does not fit the cells or a value is too large to be used as an address.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IntcodeError {
+    UnknownOpcode { opcode: i64, position: i64, base: i64 },
+    InvalidMode { mode: i64, position: i64, base: i64 },
+    ImmediateWrite { position: i64, base: i64 },
+    NegativeAddress { address: i64, position: i64, base: i64 },
+    Trap { message: String, position: i64, base: i64 },
+    Overflow { position: i64, base: i64 },
+    ResumeAfterHalt,
+}
+
+impl fmt::Display for IntcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntcodeError::UnknownOpcode { opcode, position, base } => write!(
+                f,
+                "unknown opcode {} at position {} (base {})",
+                opcode, position, base
+            ),
+            IntcodeError::InvalidMode { mode, position, base } => write!(
+                f,
+                "invalid parameter mode {} at position {} (base {})",
+                mode, position, base
+            ),
+            IntcodeError::ImmediateWrite { position, base } => write!(
+                f,
+                "writing parameter in immediate mode at position {} (base {})",
+                position, base
+            ),
+            IntcodeError::NegativeAddress { address, position, base } => {
+                write!(
+                    f,
+                    "accessing negative address {} at position {} (base {})",
+                    address, position, base
+                )
+            }
+            IntcodeError::Trap { message, position, base } => write!(
+                f,
+                "trap at position {} (base {}): {}",
+                position, base, message
+            ),
+            IntcodeError::Overflow { position, base } => {
+                write!(f, "overflow at position {} (base {})", position, base)
+            }
+            IntcodeError::ResumeAfterHalt => {
+                write!(f, "trying to resume a finished program")
+            }
+        }
+    }
+}
+
+impl error::Error for IntcodeError {}
+
+/// A fault of a single instruction, not yet tied to a machine state
+#[derive(Debug, PartialEq, Eq)]
+enum Fault {
+    UnknownOpcode(i64),
+    InvalidMode(i64),
+    ImmediateWrite,
+    NegativeAddress(i64),
+    Trap(String),
+    Overflow,
+}
+
+impl Fault {
+    fn at(self, position: i64, base: i64) -> IntcodeError {
+        match self {
+            Fault::UnknownOpcode(opcode) => {
+                IntcodeError::UnknownOpcode { opcode, position, base }
+            }
+            Fault::InvalidMode(mode) => {
+                IntcodeError::InvalidMode { mode, position, base }
+            }
+            Fault::ImmediateWrite => {
+                IntcodeError::ImmediateWrite { position, base }
+            }
+            Fault::NegativeAddress(address) => {
+                IntcodeError::NegativeAddress { address, position, base }
+            }
+            Fault::Trap(message) => {
+                IntcodeError::Trap { message, position, base }
+            }
+            Fault::Overflow => IntcodeError::Overflow { position, base },
+        }
+    }
 }
 
-pub type Program = HashMap<i64, i64>;
+pub type Program = Memory;
 pub type Input = i64;
 pub type Output = i64;
 pub type Inputs = Vec<Input>;
 pub type Outputs = Vec<Output>;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct Runner {
-    pub program: Program,
-    pub status: ExitStatus,
-    pub outputs: Outputs,
+pub struct Runner<C: Cell = i64> {
+    pub program: Memory<C>,
+    pub state: MachineState,
+    pub outputs: Vec<C>,
+    /// Kept between runs, so a program that is resumed often decodes every
+    /// instruction only once
+    cache: Cache,
 }
 
 #[cfg_attr(test, automock)]
-pub trait Step {
-    fn step(self, input: Input) -> Self;
-    fn steps(self, inputs: Inputs) -> Self;
+pub trait Step: Sized {
+    fn step(self, input: Input) -> Result<Self, IntcodeError>;
+    fn steps(self, inputs: Inputs) -> Result<Self, IntcodeError>;
     fn output(&self) -> Output;
     fn outputs(&self) -> Outputs;
 }
 
 impl Step for Runner {
-    fn step(self, input: Input) -> Runner {
+    fn step(self, input: Input) -> Result<Runner, IntcodeError> {
         let inputs = vec![input];
         self.steps(inputs)
     }
 
-    fn steps(self, inputs: Inputs) -> Runner {
-        resume(self.program, self.status, inputs)
+    fn steps(self, inputs: Inputs) -> Result<Runner, IntcodeError> {
+        resume(self, inputs)
     }
 
     fn output(&self) -> Output {
@@ -56,225 +226,530 @@ impl Step for Runner {
     }
 }
 
-pub fn start(program: Program) -> Runner {
-    let (program, status, outputs) = execute(program, 0, 0, vec![]);
-    Runner { program, status, outputs }
-}
+impl<C: Cell> Runner<C> {
+    /// A runner for the program that has not executed anything yet
+    pub fn new(program: Memory<C>) -> Runner<C> {
+        Runner::resumed(program, MachineState::new(), Vec::new())
+    }
 
-fn resume(program: Program, status: ExitStatus, inputs: Inputs) -> Runner {
-    match status {
-        ExitStatus::WaitingForInput(position, base) => {
-            let (program, status, outputs) =
-                execute(program, position, base, inputs);
-            Runner { program, status, outputs }
+    /// The runner, or the error it faulted with for callers that have no use
+    /// for a faulted machine
+    pub fn or_fault(self) -> Result<Runner<C>, IntcodeError> {
+        match self.state.status {
+            ExitStatus::Faulted(ref error) => Err(error.clone()),
+            _ => Ok(self),
         }
-        _ => panic!("Trying to resume a finished program"),
-    }
-}
-
-fn execute(
-    mut program: Program,
-    starting_position: i64,
-    starting_base: i64,
-    inputs_vec: Inputs,
-) -> (Program, ExitStatus, Outputs) {
-    let mut inputs = inputs_vec.iter();
-    let mut position = starting_position;
-    let mut base = starting_base;
-    let mut outputs = Vec::new();
-    loop {
-        let (modes, opcode) = extract_modes(program[&position]);
-
-        match opcode {
-            // exit the program
-            99 => {
-                return (program, ExitStatus::Finished, outputs);
-            }
-            // add the first and the second parameter, write to the third
-            1 => {
-                let first = find_value(position + 1, &modes[0], base, &program);
-                let second =
-                    find_value(position + 2, &modes[1], base, &program);
-                let write =
-                    writing_position(position + 3, &modes[2], base, &program);
-                program.insert(write, first + second);
-                position += 4;
-            }
-            // multiply the first and the second parameter, write to the third
-            2 => {
-                let first = find_value(position + 1, &modes[0], base, &program);
-                let second =
-                    find_value(position + 2, &modes[1], base, &program);
-                let write =
-                    writing_position(position + 3, &modes[2], base, &program);
-                program.insert(write, first * second);
-                position += 4;
-            }
-            // get an input, write it to the first parameter
-            3 => {
-                match inputs.next() {
-                    Some(&input) => {
-                        let write = writing_position(
-                            position + 1,
-                            &modes[0],
-                            base,
-                            &program,
-                        );
-                        program.insert(write, input);
-                        position += 2;
-                    }
-                    None => {
-                        return (
-                            program,
-                            ExitStatus::WaitingForInput(position, base),
-                            outputs,
-                        );
-                    }
-                };
+    }
+
+    /// A runner for the program that is at `state` and holds `outputs`
+    pub fn resumed(
+        program: Memory<C>,
+        state: MachineState,
+        outputs: Vec<C>,
+    ) -> Runner<C> {
+        Runner { program, state, outputs, cache: Cache::disabled() }
+    }
+
+    /// Run until the program finishes or the source runs dry, handing every
+    /// output to the sink as soon as it is produced
+    ///
+    /// A fault stops the program with the `Faulted` status, the returned
+    /// runner still points at the faulting instruction. Errors are only
+    /// returned for runners that cannot be resumed at all.
+    pub fn run<I, O>(
+        self,
+        source: &mut I,
+        sink: &mut O,
+    ) -> Result<Runner<C>, IntcodeError>
+    where
+        I: Source<C>,
+        O: Sink<C>,
+    {
+        self.run_traced(source, sink, &mut ())
+    }
+
+    /// Like `run`, but tells the tracer about every executed instruction
+    pub fn run_traced<I, O, T>(
+        self,
+        source: &mut I,
+        sink: &mut O,
+        tracer: &mut T,
+    ) -> Result<Runner<C>, IntcodeError>
+    where
+        I: Source<C>,
+        O: Sink<C>,
+        T: Tracer<C>,
+    {
+        self.execute(source, sink, tracer, &mut |_: &O| false, Config::new())
+    }
+
+    /// Like `run_traced`, but with a different instruction set than the
+    /// standard one
+    pub fn run_traced_with<I, O, T>(
+        self,
+        source: &mut I,
+        sink: &mut O,
+        tracer: &mut T,
+        instructions: &InstructionSet<C>,
+    ) -> Result<Runner<C>, IntcodeError>
+    where
+        I: Source<C>,
+        O: Sink<C>,
+        T: Tracer<C>,
+    {
+        let config = Config { instructions, ..Config::new() };
+        self.execute(source, sink, tracer, &mut |_: &O| false, config)
+    }
+
+    /// Like `run`, but decodes every instruction again instead of caching
+    /// them, to compare the interpreter against
+    pub fn run_uncached<I, O>(
+        self,
+        source: &mut I,
+        sink: &mut O,
+    ) -> Result<Runner<C>, IntcodeError>
+    where
+        I: Source<C>,
+        O: Sink<C>,
+    {
+        let config = Config { cached: false, ..Config::new() };
+        self.execute(source, sink, &mut (), &mut |_: &O| false, config)
+    }
+
+    /// Like `run`, but stops with the `LimitReached` status as soon as one of
+    /// the limits is hit
+    pub fn run_limited<I, O>(
+        self,
+        source: &mut I,
+        sink: &mut O,
+        limits: &Limits,
+    ) -> Result<Runner<C>, IntcodeError>
+    where
+        I: Source<C>,
+        O: Sink<C>,
+    {
+        let config = Config { limits: *limits, ..Config::new() };
+        self.execute(source, sink, &mut (), &mut |_: &O| false, config)
+    }
+
+    /// Like `run`, but with a different instruction set than the standard one
+    pub fn run_with<I, O>(
+        self,
+        source: &mut I,
+        sink: &mut O,
+        instructions: &InstructionSet<C>,
+    ) -> Result<Runner<C>, IntcodeError>
+    where
+        I: Source<C>,
+        O: Sink<C>,
+    {
+        let config = Config { instructions, ..Config::new() };
+        self.execute(source, sink, &mut (), &mut |_: &O| false, config)
+    }
+
+    /// Run until the outputs produced by this run satisfy the predicate,
+    /// which is checked after every output
+    ///
+    /// The returned runner holds those outputs and has the `OutputReady`
+    /// status when the predicate paused it.
+    pub fn run_until<I, P>(
+        self,
+        source: &mut I,
+        mut pause: P,
+    ) -> Result<Runner<C>, IntcodeError>
+    where
+        I: Source<C>,
+        P: FnMut(&[C]) -> bool,
+    {
+        let mut outputs = Vec::new();
+        let runner = self.execute(
+            source,
+            &mut outputs,
+            &mut (),
+            &mut |outputs: &Vec<C>| pause(outputs),
+            Config::new(),
+        )?;
+        Ok(Runner { outputs, ..runner })
+    }
+
+    /// Run until `count` outputs were produced, to handle output protocols
+    /// one message at a time
+    pub fn run_until_outputs<I: Source<C>>(
+        self,
+        source: &mut I,
+        count: usize,
+    ) -> Result<Runner<C>, IntcodeError> {
+        self.run_until(source, |outputs| outputs.len() == count)
+    }
+
+    /// Execute instructions until the program stops, `pause` holds for the
+    /// sink after an output or a limit is reached
+    fn execute<I, O, T, P>(
+        self,
+        source: &mut I,
+        sink: &mut O,
+        tracer: &mut T,
+        pause: &mut P,
+        config: Config<C>,
+    ) -> Result<Runner<C>, IntcodeError>
+    where
+        I: Source<C>,
+        O: Sink<C>,
+        T: Tracer<C>,
+        P: FnMut(&O) -> bool,
+    {
+        let Runner { mut program, mut state, mut cache, .. } = self;
+        match state.status {
+            ExitStatus::Halted => return Err(IntcodeError::ResumeAfterHalt),
+            ExitStatus::Faulted(error) => return Err(error),
+            _ => {}
+        }
+        let mut uncached = Cache::disabled();
+        let decoder = match config.cached {
+            true => {
+                cache.cover(&program);
+                &mut cache
             }
-            // write the first parameter to output
-            4 => {
-                let read = find_value(position + 1, &modes[0], base, &program);
-                outputs.push(read);
-                position += 2;
+            false => &mut uncached,
+        };
+
+        let started = state.cycles;
+        loop {
+            let executed = state.cycles - started;
+            if let Some(limit) = config.limits.reached(&program, executed) {
+                state.status = ExitStatus::LimitReached(limit);
+                break;
             }
-            // test the if the first parameter is not 0, if so jump to the second
-            5 => {
-                let condition =
-                    find_value(position + 1, &modes[0], base, &program);
-                let jump = find_value(position + 2, &modes[1], base, &program);
-                if condition != 0 {
-                    position = jump;
-                } else {
-                    position += 3
+            let event = if tracer.enabled() {
+                trace::Event::at(
+                    &program,
+                    state.ip,
+                    state.relative_base,
+                    config.instructions,
+                )
+            } else {
+                None
+            };
+            let result = execute_instruction(
+                &mut program,
+                &mut state.ip,
+                &mut state.relative_base,
+                source,
+                sink,
+                decoder,
+                config.instructions,
+            );
+            // an input instruction that suspends the program is not executed
+            // yet
+            let executed = match result {
+                Ok(Some(ExitStatus::WaitingForInput)) | Err(_) => false,
+                Ok(_) => true,
+            };
+            if executed {
+                state.cycles += 1;
+                if let Some(event) = event {
+                    tracer.trace(&event);
                 }
             }
-            // test the if the first parameter is 0, if so jump to the second
-            6 => {
-                let condition =
-                    find_value(position + 1, &modes[0], base, &program);
-                let jump = find_value(position + 2, &modes[1], base, &program);
-                if condition == 0 {
-                    position = jump;
-                } else {
-                    position += 3
+            match result {
+                Ok(None) => {}
+                Ok(Some(ExitStatus::OutputReady)) if !pause(sink) => {}
+                Ok(Some(status)) => {
+                    state.status = status;
+                    break;
+                }
+                Err(fault) => {
+                    let error = fault.at(state.ip, state.relative_base);
+                    state.status = ExitStatus::Faulted(error);
+                    break;
                 }
             }
-            // test the if the first parameter is smaller than the second,
-            // if so write 1 to the third, otherwise write 0
-            7 => {
-                let first = find_value(position + 1, &modes[0], base, &program);
-                let second =
-                    find_value(position + 2, &modes[1], base, &program);
-                let write =
-                    writing_position(position + 3, &modes[2], base, &program);
-                let value_to_write = match first < second {
-                    true => 1,
-                    false => 0,
-                };
-                program.insert(write, value_to_write);
-                position += 4;
-            }
-            // test the if the first parameter is equal to the second,
-            // if so write 1 to the third, otherwise write 0
-            8 => {
-                let first = find_value(position + 1, &modes[0], base, &program);
-                let second =
-                    find_value(position + 2, &modes[1], base, &program);
-                let write =
-                    writing_position(position + 3, &modes[2], base, &program);
-                let value_to_write = match first == second {
-                    true => 1,
-                    false => 0,
-                };
-                program.insert(write, value_to_write);
-                position += 4;
-            }
-            // mutate the relative base with the first parameter
-            9 => {
-                let mutation =
-                    find_value(position + 1, &modes[0], base, &program);
-                base += mutation;
-                position += 2;
-            }
-            invalid => {
-                panic!("Unknown instruction: {:?}", invalid);
-            }
         }
+        Ok(Runner { program, state, outputs: Vec::new(), cache })
+    }
+}
+
+/// How a run executes, apart from where its inputs and outputs go
+struct Config<'a, C: Cell> {
+    instructions: &'a InstructionSet<C>,
+    limits: Limits,
+    /// Whether decoded instructions are cached
+    cached: bool,
+}
+
+impl<C: Cell> Config<'static, C> {
+    fn new() -> Config<'static, C> {
+        Config {
+            instructions: C::instructions(),
+            limits: Limits::none(),
+            cached: true,
+        }
+    }
+}
+
+/// Run the program until it needs an input, faults are returned as errors
+pub fn start(program: Program) -> Result<Runner, IntcodeError> {
+    resume(Runner::new(program), Inputs::new())
+}
+
+/// Run the runner on the inputs, it only holds the outputs of this run after
+/// and keeps what it decoded
+fn resume(runner: Runner, inputs: Inputs) -> Result<Runner, IntcodeError> {
+    let mut outputs = Outputs::new();
+    let runner = Runner { outputs: Outputs::new(), ..runner }
+        .run(&mut io::iter(inputs), &mut outputs)?
+        .or_fault()?;
+    Ok(Runner { outputs, ..runner })
+}
+
+/// Execute the instruction at `position`, returning an exit status when the
+/// program stops or produced an output
+///
+/// The position and base are only updated once the instruction succeeded, so
+/// they still point at the faulting instruction when an error is returned.
+fn execute_instruction<C, I, O>(
+    program: &mut Memory<C>,
+    position: &mut i64,
+    base: &mut i64,
+    source: &mut I,
+    sink: &mut O,
+    cache: &mut Cache,
+    instructions: &InstructionSet<C>,
+) -> Result<Option<ExitStatus>, Fault>
+where
+    C: Cell,
+    I: Source<C>,
+    O: Sink<C>,
+{
+    let Decoded { opcode, modes } = cache.decode(program, *position)?;
+    let operation =
+        instructions.get(opcode).ok_or(Fault::UnknownOpcode(opcode))?;
+
+    let mut params: [C; 3] = Default::default();
+    let mut write = None;
+    for (index, mode) in modes.iter().enumerate().take(operation.arity) {
+        let offset = offset(*position, 1 + index as i64)?;
+        if operation.write == Some(index) {
+            write = Some(writing_position(offset, mode, *base, program)?);
+        } else {
+            params[index] = find_value(offset, mode, *base, program)?;
+        }
+    }
+
+    let mut context = Context {
+        params,
+        position: *position,
+        base: *base,
+        write,
+        program,
+        source,
+        sink,
+        output: false,
+    };
+    let flow = (operation.handler)(&mut context);
+    let (updated_base, output) = (context.base, context.output);
+
+    match flow {
+        Flow::Next => {
+            *position = offset(*position, 1 + operation.arity as i64)?
+        }
+        Flow::Jump(address) => *position = address,
+        Flow::Wait => return Ok(Some(ExitStatus::WaitingForInput)),
+        Flow::Halt => return Ok(Some(ExitStatus::Halted)),
+        Flow::Trap(message) => return Err(Fault::Trap(message)),
+        Flow::Overflow => return Err(Fault::Overflow),
+    }
+    *base = updated_base;
+    match output {
+        true => Ok(Some(ExitStatus::OutputReady)),
+        false => Ok(None),
     }
 }
 
-fn extract_modes(mut instruction: i64) -> (Vec<Mode>, i64) {
+fn extract_modes(mut instruction: i64) -> Result<([Mode; 3], i64), Fault> {
     let opcode = instruction % 100;
     instruction /= 100;
 
-    let mut modes = Vec::new();
-    for _ in 0..3 {
-        let mode = match instruction % 10 {
+    let mut modes = [Mode::Position; 3];
+    for mode in modes.iter_mut() {
+        *mode = match instruction % 10 {
             0 => Mode::Position,
             1 => Mode::Immediate,
             2 => Mode::Relative,
-            _ => panic!("Unknown mode given"),
+            invalid => return Err(Fault::InvalidMode(invalid)),
         };
-        modes.push(mode);
         instruction /= 10;
     }
-    (modes, opcode)
+    Ok((modes, opcode))
 }
 
-fn find_value(position: i64, mode: &Mode, base: i64, program: &Program) -> i64 {
-    let number = *program.get(&position).unwrap_or(&0);
-    match mode {
-        Mode::Position => *program.get(&number).unwrap_or(&0),
-        Mode::Immediate => number,
-        Mode::Relative => *program.get(&(base + number)).unwrap_or(&0),
+fn read<C: Cell>(address: i64, program: &Memory<C>) -> Result<C, Fault> {
+    if address < 0 {
+        return Err(Fault::NegativeAddress(address));
     }
+    Ok(program.get(address))
+}
+
+/// A cell used as an address, offset or instruction
+fn to_address<C: Cell>(value: &C) -> Result<i64, Fault> {
+    value.to_i64().ok_or(Fault::Overflow)
+}
+
+/// `address + by`, faulting when it does not fit an `i64`
+fn offset(address: i64, by: i64) -> Result<i64, Fault> {
+    address.checked_add(by).ok_or(Fault::Overflow)
 }
 
-fn writing_position(
+fn find_value<C: Cell>(
     position: i64,
     mode: &Mode,
     base: i64,
-    program: &Program,
-) -> i64 {
-    let number = *program.get(&position).unwrap_or(&0);
+    program: &Memory<C>,
+) -> Result<C, Fault> {
+    let number = read(position, program)?;
     match mode {
+        Mode::Position => read(to_address(&number)?, program),
+        Mode::Immediate => Ok(number),
+        Mode::Relative => read(offset(base, to_address(&number)?)?, program),
+    }
+}
+
+fn writing_position<C: Cell>(
+    position: i64,
+    mode: &Mode,
+    base: i64,
+    program: &Memory<C>,
+) -> Result<i64, Fault> {
+    let number = to_address(&read(position, program)?)?;
+    let address = match mode {
         Mode::Position => number,
-        Mode::Immediate => {
-            panic!("Writing parameter is not allowed to be immediate")
-        }
-        Mode::Relative => number + base,
+        Mode::Immediate => return Err(Fault::ImmediateWrite),
+        Mode::Relative => offset(base, number)?,
+    };
+    if address < 0 {
+        return Err(Fault::NegativeAddress(address));
     }
+    Ok(address)
 }
 
 pub fn load(input: &str) -> Program {
-    input
-        .trim()
-        .split(',')
-        .map(|number| number.parse())
-        .filter_map(Result::ok)
-        .enumerate()
-        .map(|(index, number)| (index as i64, number))
-        .collect()
+    load_as(input)
+}
+
+/// Like `load`, for programs with a different type of cell
+///
+/// Memory beyond the program is given as `;<address>:` followed by the values
+/// from that address on, like `dump` writes it.
+pub fn load_as<C: Cell>(input: &str) -> Memory<C> {
+    let numbers = |text: &str| -> Vec<C> {
+        text.split(',')
+            .map(|number| number.trim().parse())
+            .filter_map(Result::ok)
+            .collect()
+    };
+    let mut segments = input.trim().split(';');
+    let mut memory = Memory::from(numbers(segments.next().unwrap_or("")));
+    for segment in segments {
+        let (address, values) = match segment.split_once(':') {
+            Some((address, values)) => (address.trim().parse::<i64>(), values),
+            None => continue,
+        };
+        if let Ok(address) = address {
+            for (address, value) in (address..).zip(numbers(values)) {
+                memory.set(address, value);
+            }
+        }
+    }
+    memory
+}
+
+/// What is wrong with a program that is parsed strictly
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseError {
+    /// A cell that is not a number, or does not fit the type of cell
+    Value(String),
+    /// Memory beyond the program that lacks the `:` after its address
+    Range(String),
+    /// The address of such memory is not a number, is negative or its values
+    /// would not fit behind it
+    Address(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Value(value) => write!(f, "bad value {}", value),
+            ParseError::Range(range) => write!(f, "bad range {}", range),
+            ParseError::Address(address) => {
+                write!(f, "bad address {}", address)
+            }
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Like `load_as`, but every cell has to be valid instead of being skipped,
+/// for programs that do not come from the puzzles
+pub fn parse_as<C: Cell>(input: &str) -> Result<Memory<C>, ParseError> {
+    let numbers = |text: &str| -> Result<Vec<C>, ParseError> {
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        text.split(',')
+            .map(|number| {
+                let number = number.trim();
+                number.parse().map_err(|_| ParseError::Value(number.into()))
+            })
+            .collect()
+    };
+    let mut ranges = input.trim().split(';');
+    let mut memory = Memory::from(numbers(ranges.next().unwrap_or(""))?);
+    for range in ranges {
+        let (start, values) = range
+            .split_once(':')
+            .ok_or_else(|| ParseError::Range(range.trim().to_string()))?;
+        let start = start.trim();
+        let invalid = || ParseError::Address(start.to_string());
+        let address: i64 = start.parse().map_err(|_| invalid())?;
+        let values = numbers(values)?;
+        if address < 0 || address.checked_add(values.len() as i64).is_none() {
+            return Err(invalid());
+        }
+        for (address, value) in (address..).zip(values) {
+            memory.set(address, value);
+        }
+    }
+    Ok(memory)
+}
+
+/// The inverse of `load`, only writing the parts of memory that are populated
+pub fn dump(program: &Program) -> String {
+    let text = |cells: &[i64]| {
+        let values: Vec<_> = cells.iter().map(i64::to_string).collect();
+        values.join(",")
+    };
+    let ranges = program.ranges();
+    let mut dumped = text(ranges[0].1);
+    for &(start, cells) in &ranges[1..] {
+        dumped += &format!(";{}:{}", start, text(cells));
+    }
+    dumped
 }
 
 #[cfg(test)]
-#[macro_use]
 mod tests {
     use super::*;
-
-    macro_rules! program {
-        ( $( $x:expr ),* ) => {
-            {
-            let mut temp_program = HashMap::new();
-            let mut index = -1;
-            $(
-                index += 1;
-                temp_program.insert(index, $x);
-            )*
-            temp_program
-            }
-        };
+    use std::cell::Cell;
+    use std::sync::mpsc;
+
+    fn halted(ip: i64, cycles: u64) -> MachineState {
+        MachineState {
+            ip,
+            relative_base: 0,
+            cycles,
+            status: ExitStatus::Halted,
+        }
     }
 
     #[test]
@@ -284,11 +759,7 @@ mod tests {
 
         assert_eq!(
             start(input),
-            Runner {
-                program: output,
-                status: ExitStatus::Finished,
-                outputs: Vec::new()
-            }
+            Ok(Runner::resumed(output, halted(4, 2), Vec::new()))
         );
     }
 
@@ -299,11 +770,7 @@ mod tests {
 
         assert_eq!(
             start(input),
-            Runner {
-                program: output,
-                status: ExitStatus::Finished,
-                outputs: Vec::new()
-            }
+            Ok(Runner::resumed(output, halted(4, 2), Vec::new()))
         );
     }
 
@@ -314,11 +781,7 @@ mod tests {
 
         assert_eq!(
             start(input),
-            Runner {
-                program: output,
-                status: ExitStatus::Finished,
-                outputs: Vec::new()
-            }
+            Ok(Runner::resumed(output, halted(4, 2), Vec::new()))
         );
     }
 
@@ -329,11 +792,7 @@ mod tests {
 
         assert_eq!(
             start(input),
-            Runner {
-                program: output,
-                status: ExitStatus::Finished,
-                outputs: Vec::new()
-            }
+            Ok(Runner::resumed(output, halted(8, 3), Vec::new()))
         );
     }
 
@@ -344,51 +803,256 @@ mod tests {
 
         assert_eq!(
             start(input),
-            Runner {
-                program: output,
-                status: ExitStatus::Finished,
-                outputs: Vec::new()
-            }
+            Ok(Runner::resumed(output, halted(8, 3), Vec::new()))
         );
     }
 
     #[test]
     fn test_start_waiting_for_input_exit_status() {
         let program = program![3, 0, 4, 0, 99];
-        let runner = start(program);
-        assert_eq!(runner.status, ExitStatus::WaitingForInput(0, 0));
+        let runner = start(program).unwrap();
+        assert_eq!(runner.state.status, ExitStatus::WaitingForInput);
+        assert_eq!(runner.state.ip, 0);
     }
 
     #[test]
     fn test_resume() {
         let input = program![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
-        let exit_status = ExitStatus::WaitingForInput(8, 0);
+        let state = MachineState { ip: 8, ..MachineState::new() };
         assert_eq!(
-            resume(input.clone(), exit_status, Vec::new()),
-            Runner {
-                program: input,
-                status: ExitStatus::Finished,
-                outputs: Vec::new()
-            }
+            resume(Runner::resumed(input.clone(), state, vec![7]), Vec::new()),
+            Ok(Runner::resumed(input, halted(8, 1), Vec::new()))
         );
     }
+
+    #[test]
+    fn test_steps_keep_cache() {
+        // clear [12] once, then echo inputs forever
+        let program = program![1101, 0, 0, 12, 3, 12, 4, 12, 1105, 1, 4, 99, 0];
+
+        let runner = start(program).unwrap().step(5).unwrap().step(6).unwrap();
+
+        assert_eq!(runner.outputs, vec![6]);
+        assert!(runner.cache.holds(0));
+    }
     #[test]
-    #[should_panic]
     fn test_resume_on_finished_program() {
         let input = program![99];
-        let exit_status = ExitStatus::Finished;
-        resume(input.clone(), exit_status, Vec::new());
+        let state = halted(0, 1);
+        assert_eq!(
+            resume(Runner::resumed(input, state, Vec::new()), Vec::new()),
+            Err(IntcodeError::ResumeAfterHalt)
+        );
     }
 
     #[test]
-    #[should_panic]
     fn test_runner_step_on_finished_program() {
-        let runner = Runner {
-            program: program![99],
-            status: ExitStatus::Finished,
-            outputs: Outputs::new(),
+        let runner =
+            Runner::resumed(program![99], halted(0, 1), Outputs::new());
+        assert_eq!(
+            runner.steps(Inputs::new()),
+            Err(IntcodeError::ResumeAfterHalt)
+        );
+    }
+
+    #[test]
+    fn test_start_unknown_opcode() {
+        let input_program = program![1101, 1, 1, 5, 42, 0];
+
+        assert_eq!(
+            start(input_program),
+            Err(IntcodeError::UnknownOpcode {
+                opcode: 42,
+                position: 4,
+                base: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_start_invalid_mode() {
+        let input_program = program![109, 3, 301, 0, 0, 0, 99];
+
+        assert_eq!(
+            start(input_program),
+            Err(IntcodeError::InvalidMode { mode: 3, position: 2, base: 3 })
+        );
+    }
+
+    #[test]
+    fn test_start_immediate_write() {
+        let input_program = program![11101, 1, 1, 0, 99];
+
+        assert_eq!(
+            start(input_program),
+            Err(IntcodeError::ImmediateWrite { position: 0, base: 0 })
+        );
+    }
+
+    #[test]
+    fn test_start_negative_address() {
+        let input_program = program![109, -5, 204, 1, 99];
+
+        assert_eq!(
+            start(input_program),
+            Err(IntcodeError::NegativeAddress {
+                address: -4,
+                position: 2,
+                base: -5
+            })
+        );
+    }
+
+    #[test]
+    fn test_start_address_overflow() {
+        let input_program = program![109, i64::MAX, 204, 1, 99];
+        let overflow = IntcodeError::Overflow { position: 2, base: i64::MAX };
+
+        assert_eq!(start(input_program.clone()), Err(overflow.clone()));
+        let runner = Runner::new(input_program)
+            .run_traced(
+                &mut io::iter(vec![]),
+                &mut Vec::new(),
+                &mut trace::Profiler::new(),
+            )
+            .unwrap();
+        assert_eq!(runner.state.status, ExitStatus::Faulted(overflow.clone()));
+        assert_eq!(runner.state.ip, 2);
+        assert_eq!(runner.or_fault(), Err(overflow));
+    }
+
+    #[test]
+    fn test_start_jump_to_negative_address() {
+        let input_program = program![1105, 1, -1];
+
+        assert_eq!(
+            start(input_program),
+            Err(IntcodeError::NegativeAddress {
+                address: -1,
+                position: -1,
+                base: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_runner_run_streams_outputs() {
+        // double every input until a 0 is given
+        let input_program = program![
+            3, 100, 1006, 100, 14, 1002, 100, 2, 101, 4, 101, 1105, 1, 0, 99
+        ];
+        // feed every output back in, until it grows past 10
+        let last_output = Cell::new(1);
+        let mut source = || match last_output.get() {
+            output if output > 10 => Some(0),
+            output => Some(output),
         };
-        runner.steps(Inputs::new());
+        let mut outputs = Vec::new();
+        let mut sink = |output| {
+            last_output.set(output);
+            outputs.push(output);
+        };
+
+        let runner =
+            Runner::new(input_program).run(&mut source, &mut sink).unwrap();
+        assert_eq!(runner.state.status, ExitStatus::Halted);
+        assert_eq!(outputs, vec![2, 4, 8, 16]);
+    }
+
+    #[test]
+    fn test_runner_run_channels() {
+        let input_program = program![
+            3, 100, 1006, 100, 14, 1002, 100, 2, 101, 4, 101, 1105, 1, 0, 99
+        ];
+        let (input_sender, mut input_receiver) = mpsc::channel();
+        let (mut output_sender, output_receiver) = mpsc::channel();
+        input_sender.send(1).unwrap();
+        input_sender.send(2).unwrap();
+        drop(input_sender);
+
+        let runner = Runner::new(input_program)
+            .run(&mut input_receiver, &mut output_sender)
+            .unwrap();
+        drop(output_sender);
+
+        assert_eq!(runner.state.status, ExitStatus::WaitingForInput);
+        assert_eq!(runner.state.ip, 0);
+        assert_eq!(output_receiver.iter().collect::<Outputs>(), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_runner_run_until_outputs() {
+        // output the input twice
+        let program = program![3, 0, 4, 0, 4, 0, 99];
+
+        let runner = Runner::new(program)
+            .run_until_outputs(&mut io::iter(vec![7]), 1)
+            .unwrap();
+        assert_eq!(runner.state.status, ExitStatus::OutputReady);
+        assert_eq!((runner.state.ip, runner.state.cycles), (4, 2));
+        assert_eq!(runner.outputs, vec![7]);
+
+        let runner =
+            runner.run_until_outputs(&mut io::iter(vec![]), 2).unwrap();
+        assert_eq!(runner.state, halted(6, 4));
+        assert_eq!(runner.outputs, vec![7]);
+    }
+
+    #[test]
+    fn test_runner_run_until_predicate() {
+        // output 1, 2, 3, 4
+        let program = program![104, 1, 104, 2, 104, 3, 104, 4, 99];
+
+        let runner = Runner::new(program)
+            .run_until(&mut io::iter(vec![]), |outputs| {
+                outputs.iter().sum::<i64>() > 5
+            })
+            .unwrap();
+        assert_eq!(runner.state.status, ExitStatus::OutputReady);
+        assert_eq!(runner.outputs, vec![1, 2, 3]);
+
+        let runner = runner
+            .run_until(&mut io::iter(vec![]), |outputs| outputs.len() == 2)
+            .unwrap();
+        assert_eq!(runner.state.status, ExitStatus::Halted);
+        assert_eq!(runner.outputs, vec![4]);
+    }
+
+    #[test]
+    fn test_self_modifying_loop() {
+        // the add at 10 is cached on the first pass, then replaced by a halt
+        let program = program![
+            1105, 1, 10, 1101, 0, 99, 10, 1105, 1, 10, 1101, 0, 0, 20, 1105, 1,
+            3
+        ];
+
+        let runner = start(program).unwrap();
+
+        assert_eq!(runner.state, halted(10, 6));
+    }
+
+    #[test]
+    fn test_cached_matches_uncached() {
+        let programs = [
+            (include_str!("../input/day_02.txt"), vec![]),
+            (include_str!("../input/day_05.txt"), vec![5]),
+            (include_str!("../input/day_09.txt"), vec![2]),
+        ];
+        for (input, inputs) in programs.iter() {
+            let runner = Runner::new(load(input));
+            let (mut cached, mut uncached) = (Vec::new(), Vec::new());
+
+            let cached_runner = runner
+                .clone()
+                .run(&mut io::iter(inputs.clone()), &mut cached)
+                .unwrap();
+            let uncached_runner = runner
+                .run_uncached(&mut io::iter(inputs.clone()), &mut uncached)
+                .unwrap();
+
+            assert_eq!(cached_runner, uncached_runner);
+            assert_eq!(cached, uncached);
+        }
     }
 
     #[test]
@@ -398,15 +1062,11 @@ mod tests {
         let output_program = program![1, 0, 4, 0, 99];
         let outputs = vec![1];
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(
             runner,
-            Runner {
-                program: output_program,
-                status: ExitStatus::Finished,
-                outputs: outputs
-            }
+            Runner::resumed(output_program, halted(4, 3), outputs)
         );
     }
 
@@ -416,10 +1076,10 @@ mod tests {
         let input_program = program![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
         let input = 8;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![1]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -428,10 +1088,10 @@ mod tests {
         let input_program = program![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
         let input = 7;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![0]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -440,10 +1100,10 @@ mod tests {
         let input_program = program![3, 9, 7, 9, 10, 9, 4, 9, 99, -1, 8];
         let input = 7;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![1]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -452,10 +1112,10 @@ mod tests {
         let input_program = program![3, 9, 7, 9, 10, 9, 4, 9, 99, -1, 8];
         let input = 8;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![0]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -464,10 +1124,10 @@ mod tests {
         let input_program = program![3, 3, 1108, -1, 8, 3, 4, 3, 99];
         let input = 8;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![1]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -476,10 +1136,10 @@ mod tests {
         let input_program = program![3, 3, 1108, -1, 8, 3, 4, 3, 99];
         let input = 7;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![0]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -488,10 +1148,10 @@ mod tests {
         let input_program = program![3, 3, 1107, -1, 8, 3, 4, 3, 99];
         let input = 7;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![1]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -500,10 +1160,10 @@ mod tests {
         let input_program = program![3, 3, 1107, -1, 8, 3, 4, 3, 99];
         let input = 8;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![0]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -513,10 +1173,10 @@ mod tests {
             program![3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9];
         let input = 0;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![0]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -526,10 +1186,10 @@ mod tests {
             program![3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9];
         let input = 2;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![1]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -539,10 +1199,10 @@ mod tests {
             program![3, 3, 1105, -1, 9, 1101, 0, 0, 12, 4, 12, 99, 1];
         let input = 0;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![0]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -552,10 +1212,10 @@ mod tests {
             program![3, 3, 1105, -1, 9, 1101, 0, 0, 12, 4, 12, 99, 1];
         let input = 2;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![1]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -568,10 +1228,10 @@ mod tests {
         ];
         let input = 7;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![999]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -584,10 +1244,10 @@ mod tests {
         ];
         let input = 8;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![1000]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -600,10 +1260,10 @@ mod tests {
         ];
         let input = 9;
 
-        let mut runner = start(input_program);
-        runner = runner.step(input);
+        let mut runner = start(input_program).unwrap();
+        runner = runner.step(input).unwrap();
         assert_eq!(runner.outputs, vec![1001]);
-        assert_eq!(runner.status, ExitStatus::Finished);
+        assert_eq!(runner.state.status, ExitStatus::Halted);
     }
 
     #[test]
@@ -618,7 +1278,7 @@ mod tests {
             0, 99,
         ];
 
-        let runner = start(input_program);
+        let runner = start(input_program).unwrap();
         assert_eq!(runner.outputs, expected_outputs);
     }
 
@@ -627,7 +1287,7 @@ mod tests {
         // should output a 16-digit number
         let input_program = program![1102, 34915192, 34915192, 7, 4, 7, 99, 0];
 
-        let runner = start(input_program);
+        let runner = start(input_program).unwrap();
         assert_eq!(runner.outputs[0], 1219070632396864);
     }
 
@@ -636,7 +1296,7 @@ mod tests {
         // should output a 16-digit number
         let input_program = program![104, 1125899906842624, 99];
 
-        let runner = start(input_program);
+        let runner = start(input_program).unwrap();
         assert_eq!(runner.outputs[0], 1125899906842624);
     }
 
@@ -647,20 +1307,60 @@ mod tests {
 
         assert_eq!(
             start(input),
-            Runner {
-                program: output,
-                status: ExitStatus::Finished,
-                outputs: Vec::new()
-            }
+            Ok(Runner::resumed(output, halted(4, 2), Vec::new()))
+        );
+    }
+
+    #[test]
+    fn test_dump() {
+        let input = "1,9,10,3,2,3,11,0,99,30,40,50";
+
+        assert_eq!(dump(&load(input)), input);
+    }
+
+    #[test]
+    fn test_dump_sparse() {
+        let mut program = load("1,2,3");
+        program.set(1 << 40, 4);
+        program.set((1 << 40) + 2, 5);
+
+        let dumped = dump(&program);
+        assert_eq!(dumped, "1,2,3;1099511627776:4,0,5");
+        assert_eq!(load(&dumped), program);
+    }
+
+    #[test]
+    fn test_parse_strictly() {
+        assert_eq!(parse_as("1,2,3\n"), Ok(program![1, 2, 3]));
+        assert_eq!(parse_as::<i64>(""), Ok(Program::default()));
+        let mut sparse = program![1, 2];
+        sparse.set(5000, 3);
+        assert_eq!(parse_as("1,2;5000:3"), Ok(sparse));
+
+        assert_eq!(
+            parse_as::<i64>("1,x,0,0,99"),
+            Err(ParseError::Value("x".to_string()))
+        );
+        assert_eq!(
+            parse_as::<i64>("1,2,"),
+            Err(ParseError::Value("".to_string()))
+        );
+        assert_eq!(
+            parse_as::<i64>("1;5000"),
+            Err(ParseError::Range("5000".to_string()))
+        );
+        assert_eq!(
+            parse_as::<i64>("1;-5:1"),
+            Err(ParseError::Address("-5".to_string()))
         );
     }
 
     #[test]
     fn test_extract_modes() {
         let input = 1002;
-        let output = (vec![Mode::Position, Mode::Immediate, Mode::Position], 2);
+        let output = ([Mode::Position, Mode::Immediate, Mode::Position], 2);
 
-        assert_eq!(extract_modes(input), output);
+        assert_eq!(extract_modes(input), Ok(output));
     }
 
     #[test]
@@ -670,7 +1370,7 @@ mod tests {
         let position = 1;
 
         let output = 33;
-        assert_eq!(find_value(position, mode, 0, program), output);
+        assert_eq!(find_value(position, mode, 0, program), Ok(output));
     }
 
     #[test]
@@ -680,7 +1380,7 @@ mod tests {
         let position = 2;
 
         let output = 3;
-        assert_eq!(find_value(position, mode, 0, program), output);
+        assert_eq!(find_value(position, mode, 0, program), Ok(output));
     }
 
     #[test]
@@ -690,7 +1390,7 @@ mod tests {
         let position = 2;
 
         let output = 4;
-        assert_eq!(find_value(position, mode, -2, program), output);
+        assert_eq!(find_value(position, mode, -2, program), Ok(output));
     }
 
     #[test]
@@ -701,18 +1401,23 @@ mod tests {
         let base = 0;
 
         let expected = 3;
-        assert_eq!(writing_position(position, mode, base, &program), expected)
+        assert_eq!(
+            writing_position(position, mode, base, &program),
+            Ok(expected)
+        )
     }
 
     #[test]
-    #[should_panic]
     fn test_writing_position_immediate_mode() {
         let program = program![103, 0, 99];
         let mode = &Mode::Immediate;
         let position = 1;
         let base = 0;
 
-        writing_position(position, mode, base, &program);
+        assert_eq!(
+            writing_position(position, mode, base, &program),
+            Err(Fault::ImmediateWrite)
+        );
     }
 
     #[test]
@@ -723,6 +1428,9 @@ mod tests {
         let base = 3;
 
         let expected = 3;
-        assert_eq!(writing_position(position, mode, base, &program), expected)
+        assert_eq!(
+            writing_position(position, mode, base, &program),
+            Ok(expected)
+        )
     }
 }
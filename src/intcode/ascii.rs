@@ -0,0 +1,185 @@
+use intcode::{io, ExitStatus, Inputs, IntcodeError, Output, Program, Runner};
+use std::io::{self as stdio, BufRead, Write};
+use std::mem;
+
+/// What a program printed, split into text and the values that are not ASCII
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Printed {
+    pub text: String,
+    /// Outputs outside of the ASCII range, like a final numeric answer
+    pub values: Vec<Output>,
+}
+
+/// The inputs that type a line of text, ending with a newline
+pub fn encode(line: &str) -> Inputs {
+    line.chars().chain("\n".chars()).map(|c| c as i64).collect()
+}
+
+pub fn decode(outputs: &[Output]) -> Printed {
+    let mut printed = Printed::default();
+    for &output in outputs {
+        match output {
+            0..=127 => printed.text.push(output as u8 as char),
+            _ => printed.values.push(output),
+        }
+    }
+    printed
+}
+
+/// A program that is talked to in lines of text
+pub struct Ascii {
+    runner: Runner,
+}
+
+impl Ascii {
+    pub fn new(program: Program) -> Ascii {
+        Ascii { runner: Runner::new(program) }
+    }
+
+    pub fn status(&self) -> &ExitStatus {
+        &self.runner.state.status
+    }
+
+    /// Type the lines, then run until the program waits for more input or
+    /// halts, returning what it printed in the meantime
+    ///
+    /// After a fault the program is kept as it was, sending to it again
+    /// returns the same fault.
+    pub fn send(&mut self, lines: &[&str]) -> Result<Printed, IntcodeError> {
+        match self.runner.state.status {
+            ExitStatus::Halted => return Err(IntcodeError::ResumeAfterHalt),
+            ExitStatus::Faulted(ref error) => return Err(error.clone()),
+            _ => {}
+        }
+        let inputs = lines.iter().flat_map(|line| encode(line));
+        let mut outputs = Vec::new();
+        let runner =
+            mem::replace(&mut self.runner, Runner::new(Program::default()));
+        self.runner = runner
+            .run(&mut io::iter(inputs), &mut outputs)
+            .expect("Only halted and faulted programs cannot be resumed");
+        if let ExitStatus::Faulted(ref error) = self.runner.state.status {
+            return Err(error.clone());
+        }
+        Ok(decode(&outputs))
+    }
+}
+
+/// Let someone use the program from a terminal: everything it prints is
+/// shown, every line read is typed into the program
+///
+/// Stops when the program halts or faults, or when `lines` runs out.
+pub fn interact<R: BufRead, W: Write>(
+    program: Program,
+    lines: R,
+    out: &mut W,
+) -> stdio::Result<()> {
+    let mut ascii = Ascii::new(program);
+    let mut lines = lines.lines();
+    let mut printed = ascii.send(&[]);
+    loop {
+        match printed {
+            Ok(printed) => show(&printed, out)?,
+            Err(error) => return writeln!(out, "fault: {}", error),
+        }
+        if ascii.status() == &ExitStatus::Halted {
+            return Ok(());
+        }
+        out.flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        printed = ascii.send(&[&line]);
+    }
+}
+
+fn show<W: Write>(printed: &Printed, out: &mut W) -> stdio::Result<()> {
+    write!(out, "{}", printed.text)?;
+    if printed.values.is_empty() {
+        return Ok(());
+    }
+    if !printed.text.is_empty() && !printed.text.ends_with('\n') {
+        writeln!(out)?;
+    }
+    for value in &printed.values {
+        writeln!(out, "{}", value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::asm::assemble;
+
+    // show a prompt, echo everything typed until a 'q', then answer 1000
+    const ECHO: &str = "
+            OUT #62
+    loop:   IN [c]
+            EQ [c], #113, [t]
+            JT [t], #done
+            OUT [c]
+            JT #1, #loop
+    done:   OUT #1000
+            HLT
+    c:      data 0
+    t:      data 0";
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("Hi"), vec![72, 105, 10]);
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode(&[79, 75, 10, 1_219_070, -1]),
+            Printed { text: "OK\n".to_string(), values: vec![1_219_070, -1] }
+        );
+    }
+
+    #[test]
+    fn test_send() {
+        let mut ascii = Ascii::new(assemble(ECHO).unwrap());
+
+        assert_eq!(ascii.send(&[]).unwrap().text, ">");
+        assert_eq!(
+            ascii.send(&["hello", "there"]).unwrap().text,
+            "hello\nthere\n"
+        );
+        assert_eq!(ascii.status(), &ExitStatus::WaitingForInput);
+
+        let printed = ascii.send(&["quit"]).unwrap();
+        assert_eq!(
+            printed,
+            Printed { text: String::new(), values: vec![1000] }
+        );
+        assert_eq!(ascii.status(), &ExitStatus::Halted);
+    }
+
+    #[test]
+    fn test_send_after_fault() {
+        let mut ascii = Ascii::new(program![3, 0, 42]);
+
+        let error = ascii.send(&["x"]).unwrap_err();
+
+        assert_eq!(ascii.send(&["y"]), Err(error.clone()));
+        assert_eq!(ascii.status(), &ExitStatus::Faulted(error));
+        assert_eq!(ascii.runner.program.get(0), 120);
+    }
+
+    #[test]
+    fn test_interact() {
+        let mut out = Vec::new();
+
+        interact(
+            assemble(ECHO).unwrap(),
+            &b"ab\nq\nnever read\n"[..],
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), ">ab\n1000\n");
+    }
+}
@@ -0,0 +1,361 @@
+use intcode::isa::{self, EXTENDED};
+use intcode::Program;
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+/// Assemble a program from text
+///
+/// Every line holds an optional label (`name:`), followed by either an
+/// instruction or a `data` directive. Comments start with `;`.
+///
+///     start:  IN [value]          ; read a number
+///             JF [value], #end    ; stop at 0
+///             MUL [value], #2, [value]
+///             OUT [value]
+///             JT #1, #start
+///     end:    HLT
+///     value:  data 0
+///
+/// Operands are written like the disassembler shows them: `[address]` for
+/// position mode, `#value` for immediate mode and `rb+offset` for relative
+/// mode. Addresses and values can be numbers or labels. The addresses in a
+/// listing produced by the disassembler (`  12: ADD ...`) are checked against
+/// the address the line ends up at, so listings can be assembled again.
+/// Besides the standard instructions, the ones of `isa::extensions()` can be
+/// used, the program is meant to run with `isa::EXTENDED`.
+pub fn assemble(source: &str) -> Result<Program, AsmError> {
+    let mut labels = HashMap::new();
+    let mut statements = Vec::new();
+    let mut address = 0;
+
+    // lay out the program and find the address of every label
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let error = |message: String| AsmError { line: number, message };
+
+        let mut rest = line.split(';').next().unwrap().trim();
+        while let Some(colon) = rest.find(':') {
+            let label = rest[..colon].trim();
+            if let Ok(expected) = label.parse::<i64>() {
+                if expected != address {
+                    return Err(error(format!(
+                        "Line is at address {}, not {}",
+                        address, expected
+                    )));
+                }
+            } else if is_identifier(label) {
+                if labels.insert(label.to_string(), address).is_some() {
+                    return Err(error(format!("Duplicate label: {}", label)));
+                }
+            } else {
+                return Err(error(format!("Invalid label: {}", label)));
+            }
+            rest = rest[colon + 1..].trim();
+        }
+        if rest.is_empty() {
+            continue;
+        }
+
+        let statement = parse_statement(rest).map_err(error)?;
+        address += statement.len() as i64;
+        statements.push((number, statement));
+    }
+
+    // encode every statement now that all labels are known
+    let mut program = Vec::new();
+    for (number, statement) in statements {
+        let error = |message: String| AsmError { line: number, message };
+        let resolve = |value: &Value| match value {
+            Value::Number(number) => Ok(*number),
+            Value::Label(label) => labels
+                .get(label)
+                .cloned()
+                .ok_or_else(|| error(format!("Unknown label: {}", label))),
+        };
+
+        match statement {
+            Statement::Data(values) => {
+                for value in values.iter() {
+                    program.push(resolve(value)?);
+                }
+            }
+            Statement::Instruction(opcode, operands) => {
+                let mut instruction = opcode;
+                let mut parameters = Vec::new();
+                for (index, operand) in operands.iter().enumerate() {
+                    let (mode, sign, value) = match operand {
+                        Operand::Position(value) => (0, 1, value),
+                        Operand::Immediate(value) => (1, 1, value),
+                        Operand::Relative(sign, value) => (2, *sign, value),
+                    };
+                    instruction += mode * 10_i64.pow(index as u32 + 2);
+                    let value = resolve(value)?;
+                    let parameter = match sign {
+                        1 => Some(value),
+                        _ => value.checked_neg(),
+                    };
+                    parameters.push(parameter.ok_or_else(|| {
+                        error(format!("Offset out of range: rb-{}", value))
+                    })?);
+                }
+                program.push(instruction);
+                program.extend(parameters);
+            }
+        }
+    }
+    Ok(Program::from(program))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AsmError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for AsmError {}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Value {
+    Number(i64),
+    Label(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Operand {
+    Position(Value),
+    Immediate(Value),
+    Relative(i64, Value),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Statement {
+    Instruction(i64, Vec<Operand>),
+    Data(Vec<Value>),
+}
+
+impl Statement {
+    /// Amount of memory cells the statement occupies
+    fn len(&self) -> usize {
+        match self {
+            Statement::Instruction(_, operands) => operands.len() + 1,
+            Statement::Data(values) => values.len(),
+        }
+    }
+}
+
+fn parse_statement(text: &str) -> Result<Statement, String> {
+    let (word, rest) = match text.find(char::is_whitespace) {
+        Some(split) => (&text[..split], text[split..].trim()),
+        None => (text, ""),
+    };
+    let arguments: Vec<&str> = if rest.is_empty() {
+        Vec::new()
+    } else {
+        rest.split(',').map(str::trim).collect()
+    };
+
+    if word.eq_ignore_ascii_case("data") {
+        let values = arguments
+            .iter()
+            .map(|argument| parse_value(argument))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Statement::Data(values));
+    }
+
+    let &isa::Operation { mnemonic, opcode, arity, write, .. } = EXTENDED
+        .find(word)
+        .ok_or_else(|| format!("Unknown mnemonic: {}", word))?;
+    if arguments.len() != arity {
+        return Err(format!(
+            "{} takes {} operands, got {}",
+            mnemonic,
+            arity,
+            arguments.len()
+        ));
+    }
+
+    let mut operands = Vec::new();
+    for (index, argument) in arguments.iter().enumerate() {
+        let operand = parse_operand(argument)?;
+        if let Operand::Immediate(_) = operand {
+            if write == Some(index) {
+                return Err(format!(
+                    "{} cannot write to an immediate operand",
+                    mnemonic
+                ));
+            }
+        }
+        operands.push(operand);
+    }
+    Ok(Statement::Instruction(opcode, operands))
+}
+
+fn parse_operand(text: &str) -> Result<Operand, String> {
+    if text.starts_with('[') && text.ends_with(']') {
+        let value = parse_value(&text[1..text.len() - 1])?;
+        Ok(Operand::Position(value))
+    } else if let Some(value) = text.strip_prefix('#') {
+        Ok(Operand::Immediate(parse_value(value)?))
+    } else if let Some(offset) = text.strip_prefix("rb") {
+        let offset = offset.trim();
+        if let Some(value) = offset.strip_prefix('+') {
+            Ok(Operand::Relative(1, parse_value(value)?))
+        } else if let Some(value) = offset.strip_prefix('-') {
+            Ok(Operand::Relative(-1, parse_value(value)?))
+        } else {
+            Err(format!("Invalid relative operand: {}", text))
+        }
+    } else {
+        Err(format!("Invalid operand: {}", text))
+    }
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    if let Ok(number) = text.parse() {
+        Ok(Value::Number(number))
+    } else if is_identifier(text) {
+        Ok(Value::Label(text.to_string()))
+    } else {
+        Err(format!("Invalid value: {}", text))
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::disasm::listing;
+    use intcode::{io, load, ExitStatus, Runner};
+
+    const DOUBLER: &str = "
+        start:  IN [value]          ; read a number
+                JF [value], #end    ; stop at 0
+                MUL [value], #2, [value]
+                OUT [value]
+                JT #1, #start
+        end:    HLT
+        value:  data 0
+    ";
+
+    #[test]
+    fn test_assemble() {
+        let expected = program![
+            3, 15, 1006, 15, 14, 1002, 15, 2, 15, 4, 15, 1105, 1, 0, 99, 0
+        ];
+
+        assert_eq!(assemble(DOUBLER), Ok(expected));
+    }
+
+    #[test]
+    fn test_assemble_runs() {
+        let program = assemble(DOUBLER).unwrap();
+        let mut outputs = Vec::new();
+
+        let runner = Runner::new(program)
+            .run(&mut io::iter(vec![4, 21, 0]), &mut outputs)
+            .unwrap();
+
+        assert_eq!(runner.state.status, ExitStatus::Halted);
+        assert_eq!(outputs, vec![8, 42]);
+    }
+
+    #[test]
+    fn test_assemble_relative_offsets() {
+        assert_eq!(
+            assemble("ARB #-3\nADD rb-1, rb+2, rb+0"),
+            Ok(program![109, -3, 22201, -1, 2, 0])
+        );
+    }
+
+    #[test]
+    fn test_assemble_unknown_mnemonic() {
+        assert_eq!(
+            assemble("HLT\nJMP #0"),
+            Err(AsmError {
+                line: 2,
+                message: "Unknown mnemonic: JMP".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_assemble_unknown_label() {
+        assert_eq!(
+            assemble("JT #1, #nowhere"),
+            Err(AsmError {
+                line: 1,
+                message: "Unknown label: nowhere".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_assemble_immediate_write() {
+        assert_eq!(
+            assemble("IN #5"),
+            Err(AsmError {
+                line: 1,
+                message: "IN cannot write to an immediate operand".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_assemble_offset_out_of_range() {
+        assert_eq!(
+            assemble("OUT rb--9223372036854775808"),
+            Err(AsmError {
+                line: 1,
+                message: "Offset out of range: rb--9223372036854775808"
+                    .to_string()
+            })
+        );
+        assert_eq!(
+            assemble("OUT rb--9223372036854775807"),
+            Ok(program![204, 9223372036854775807])
+        );
+    }
+
+    #[test]
+    fn test_assemble_address_mismatch() {
+        assert_eq!(
+            assemble("0: HLT\n2: HLT"),
+            Err(AsmError {
+                line: 2,
+                message: "Line is at address 1, not 2".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_round_trip_puzzle_inputs() {
+        let inputs = [
+            include_str!("../../input/day_02.txt"),
+            include_str!("../../input/day_05.txt"),
+            include_str!("../../input/day_09.txt"),
+            include_str!("../../input/day_13.txt"),
+            include_str!("../../input/day_15.txt"),
+        ];
+        for input in inputs.iter() {
+            let program = load(input);
+            assert_eq!(assemble(&listing(&program, &EXTENDED)), Ok(program));
+        }
+    }
+}
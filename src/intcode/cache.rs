@@ -0,0 +1,129 @@
+use intcode::{extract_modes, read, to_address, Cell, Fault, Memory, Mode};
+
+/// An instruction whose opcode and modes were split up already
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(super) struct Decoded {
+    pub opcode: i64,
+    pub modes: [Mode; 3],
+}
+
+/// Decoded instructions by address, so every instruction of a loop is only
+/// decoded the first time it executes
+///
+/// Only the instruction value itself is cached, parameters are always read
+/// from memory. Every entry remembers the value it was decoded from and is
+/// dropped once memory holds something else, which keeps self-modifying
+/// programs exact however memory was written. Only the loaded image is
+/// covered, code rarely lives beyond it.
+#[derive(Debug, Default, Clone)]
+pub(super) struct Cache {
+    entries: Vec<Option<(i64, Decoded)>>,
+}
+
+impl Cache {
+    /// A cache that holds nothing until it covers a program, every
+    /// instruction is decoded again
+    pub fn disabled() -> Cache {
+        Cache::default()
+    }
+
+    /// Make the cache cover the loaded image of the program, keeping what it
+    /// holds already
+    pub fn cover<C: Cell>(&mut self, program: &Memory<C>) {
+        self.entries.resize(program.loaded(), None);
+    }
+
+    /// Whether the instruction at the address is decoded already
+    #[cfg(test)]
+    pub fn holds(&self, address: usize) -> bool {
+        matches!(self.entries.get(address), Some(Some(_)))
+    }
+
+    pub fn decode<C: Cell>(
+        &mut self,
+        program: &Memory<C>,
+        address: i64,
+    ) -> Result<Decoded, Fault> {
+        let instruction = to_address(&read(address, program)?)?;
+        let entry = match self.entries.get_mut(address as usize) {
+            Some(Some((cached, decoded))) if *cached == instruction => {
+                return Ok(*decoded)
+            }
+            entry => entry,
+        };
+
+        let (modes, opcode) = extract_modes(instruction)?;
+        let decoded = Decoded { opcode, modes };
+        if let Some(entry) = entry {
+            *entry = Some((instruction, decoded));
+        }
+        Ok(decoded)
+    }
+}
+
+/// Caches never change how a program runs, so runners compare equal
+/// whatever their caches hold
+impl PartialEq for Cache {
+    fn eq(&self, _: &Cache) -> bool {
+        true
+    }
+}
+
+impl Eq for Cache {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_is_cached() {
+        let program = program![1002, 4, 3, 4, 33];
+        let mut cache = Cache::disabled();
+        cache.cover(&program);
+        cache.decode(&program, 0).unwrap();
+
+        assert_eq!(cache.entries[0].map(|(value, _)| value), Some(1002));
+        assert_eq!(cache.decode(&program, 0).unwrap().opcode, 2);
+    }
+
+    #[test]
+    fn test_write_invalidates() {
+        let mut program = program![1002, 4, 3, 4, 33];
+        let mut cache = Cache::disabled();
+        cache.cover(&program);
+        cache.decode(&program, 0).unwrap();
+
+        program.set(0, 1101);
+
+        assert_eq!(
+            cache.decode(&program, 0),
+            Ok(Decoded {
+                opcode: 1,
+                modes: [Mode::Immediate, Mode::Immediate, Mode::Position],
+            })
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let program = program![1002, 4, 3, 4, 33];
+        let mut cache = Cache::disabled();
+
+        assert_eq!(cache.decode(&program, 0).unwrap().opcode, 2);
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn test_covers_loaded_image() {
+        let mut program = program![1002, 4, 3, 4, 33];
+        program.set(1_000_000_000_000, 99);
+        let mut cache = Cache::disabled();
+        cache.cover(&program);
+
+        assert_eq!(cache.entries.len(), 5);
+        assert_eq!(
+            cache.decode(&program, 1_000_000_000_000).unwrap().opcode,
+            99
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use intcode::isa::{self, InstructionSet, STANDARD};
+use num::{BigInt, ToPrimitive};
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// A value in the memory of an Intcode computer
+///
+/// The default value is 0. Addresses, offsets and instructions are plain
+/// `i64`s, cells that do not fit one fault with `IntcodeError::Overflow` when
+/// they are used as such.
+pub trait Cell:
+    Clone + Default + Ord + fmt::Debug + fmt::Display + FromStr + 'static
+{
+    fn from_i64(value: i64) -> Self;
+    fn to_i64(&self) -> Option<i64>;
+    /// The sum, or `None` when it does not fit this type of cell
+    ///
+    /// What happens on overflow depends on the type: `i64` wraps around and
+    /// never returns `None`, `Checked` and `i128` return `None`, `BigInt`
+    /// never overflows.
+    fn add(&self, other: &Self) -> Option<Self>;
+    /// The product, overflowing like `add` does
+    fn mul(&self, other: &Self) -> Option<Self>;
+    /// The standard instruction set for this type of cell
+    fn instructions() -> &'static InstructionSet<Self>;
+}
+
+/// Wraps around on overflow, like the interpreter always did in release
+/// builds
+impl Cell for i64 {
+    fn from_i64(value: i64) -> i64 {
+        value
+    }
+
+    fn to_i64(&self) -> Option<i64> {
+        Some(*self)
+    }
+
+    fn add(&self, other: &i64) -> Option<i64> {
+        Some(self.wrapping_add(*other))
+    }
+
+    fn mul(&self, other: &i64) -> Option<i64> {
+        Some(self.wrapping_mul(*other))
+    }
+
+    fn instructions() -> &'static InstructionSet<i64> {
+        &STANDARD
+    }
+}
+
+/// An `i64` that faults on overflow instead of wrapping around
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checked(pub i64);
+
+impl fmt::Display for Checked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Checked {
+    type Err = ParseIntError;
+
+    fn from_str(text: &str) -> Result<Checked, ParseIntError> {
+        text.parse().map(Checked)
+    }
+}
+
+impl Cell for Checked {
+    fn from_i64(value: i64) -> Checked {
+        Checked(value)
+    }
+
+    fn to_i64(&self) -> Option<i64> {
+        Some(self.0)
+    }
+
+    fn add(&self, other: &Checked) -> Option<Checked> {
+        self.0.checked_add(other.0).map(Checked)
+    }
+
+    fn mul(&self, other: &Checked) -> Option<Checked> {
+        self.0.checked_mul(other.0).map(Checked)
+    }
+
+    fn instructions() -> &'static InstructionSet<Checked> {
+        &CHECKED
+    }
+}
+
+/// Faults on overflow, which takes numbers far beyond what puzzles use
+impl Cell for i128 {
+    fn from_i64(value: i64) -> i128 {
+        i128::from(value)
+    }
+
+    fn to_i64(&self) -> Option<i64> {
+        ToPrimitive::to_i64(self)
+    }
+
+    fn add(&self, other: &i128) -> Option<i128> {
+        i128::checked_add(*self, *other)
+    }
+
+    fn mul(&self, other: &i128) -> Option<i128> {
+        i128::checked_mul(*self, *other)
+    }
+
+    fn instructions() -> &'static InstructionSet<i128> {
+        &WIDE
+    }
+}
+
+/// Never overflows, at the cost of an allocation for most values
+impl Cell for BigInt {
+    fn from_i64(value: i64) -> BigInt {
+        BigInt::from(value)
+    }
+
+    fn to_i64(&self) -> Option<i64> {
+        ToPrimitive::to_i64(self)
+    }
+
+    fn add(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self + other)
+    }
+
+    fn mul(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self * other)
+    }
+
+    fn instructions() -> &'static InstructionSet<BigInt> {
+        &BIG
+    }
+}
+
+lazy_static! {
+    static ref CHECKED: InstructionSet<Checked> = isa::standard();
+    static ref WIDE: InstructionSet<i128> = isa::standard();
+    static ref BIG: InstructionSet<BigInt> = isa::standard();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::{io, load_as, ExitStatus, IntcodeError, Runner};
+
+    // square the input twice, then output it
+    const SQUARE_TWICE: &str = "3,13,2,13,13,13,2,13,13,13,4,13,99,0";
+
+    fn square_twice<C: Cell>(input: C) -> Result<Vec<C>, IntcodeError> {
+        let mut outputs = Vec::new();
+        Runner::new(load_as::<C>(SQUARE_TWICE))
+            .run(&mut io::iter(vec![input]), &mut outputs)?
+            .or_fault()?;
+        Ok(outputs)
+    }
+
+    #[test]
+    fn test_i64_wraps() {
+        assert_eq!(square_twice(1i64 << 20), Ok(vec![0]));
+    }
+
+    #[test]
+    fn test_checked_faults_on_overflow() {
+        assert_eq!(
+            square_twice(Checked(1000)),
+            Ok(vec![Checked(1_000_000_000_000)])
+        );
+        assert_eq!(
+            square_twice(Checked(1 << 20)),
+            Err(IntcodeError::Overflow { position: 6, base: 0 })
+        );
+    }
+
+    #[test]
+    fn test_i128() {
+        assert_eq!(square_twice(1i128 << 20), Ok(vec![1 << 80]));
+        assert!(square_twice(1i128 << 40).is_err());
+    }
+
+    #[test]
+    fn test_bigint() {
+        let input = BigInt::from(1u64 << 40);
+
+        assert_eq!(square_twice(input), Ok(vec![BigInt::from(1) << 160]));
+    }
+
+    #[test]
+    fn test_address_out_of_range() {
+        // jump to an address that does not fit an i64
+        let program = load_as::<i128>("1105,1,36893488147419103232");
+
+        let runner =
+            Runner::new(program).run(&mut io::iter(vec![]), &mut Vec::new());
+        assert_eq!(
+            runner.unwrap().state.status,
+            ExitStatus::Faulted(IntcodeError::Overflow {
+                position: 0,
+                base: 0
+            })
+        );
+    }
+}
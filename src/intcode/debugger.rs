@@ -0,0 +1,475 @@
+use intcode::cache::Cache;
+use intcode::disasm::{self, Line};
+use intcode::isa::InstructionSet;
+use intcode::{execute_instruction, ExitStatus, Input, MachineState, Program};
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+step [n]            execute the next n instructions (default 1)
+continue            run until a breakpoint, watchpoint, halt or missing input
+break <address>     stop before executing the instruction at an address
+break op <opcode>   stop before executing an opcode, by number or mnemonic
+watch <address>     stop after the value at an address changed
+delete              remove all breakpoints and watchpoints
+input <values..>    queue input values for the program
+print <address> [n] print n values of memory (default 1)
+set <address> <v>   write a value to memory
+regs                print the instruction pointer and relative base
+list [n]            disassemble n instructions from the instruction pointer
+quit                stop debugging";
+
+/// An Intcode machine that is executed one instruction at a time, driven by
+/// textual commands
+pub struct Debugger<'a> {
+    program: Program,
+    /// What the program runs with, also used to list it and to look up the
+    /// opcodes of breakpoints
+    instructions: &'a InstructionSet,
+    state: MachineState,
+    inputs: VecDeque<Input>,
+    breakpoints: BTreeSet<i64>,
+    opcode_breakpoints: BTreeSet<i64>,
+    watchpoints: BTreeSet<i64>,
+}
+
+/// Why the machine did not execute the next instruction
+enum Stop {
+    Breakpoint,
+    Watchpoint(i64, i64, i64),
+    Halted,
+    WaitingForInput,
+    Fault(String),
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: Program, instructions: &'a InstructionSet) -> Self {
+        Debugger {
+            program,
+            instructions,
+            state: MachineState::new(),
+            inputs: VecDeque::new(),
+            breakpoints: BTreeSet::new(),
+            opcode_breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+        }
+    }
+
+    /// Read commands line by line until `quit` or the end of the input
+    pub fn run<R: BufRead, W: Write>(
+        &mut self,
+        commands: R,
+        out: &mut W,
+    ) -> io::Result<()> {
+        self.show_position(out)?;
+        for line in commands.lines() {
+            let line = line?;
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.first() == Some(&"quit") {
+                break;
+            }
+            if let Err(message) = self.command(&words, out)? {
+                writeln!(out, "error: {}", message)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn command<W: Write>(
+        &mut self,
+        words: &[&str],
+        out: &mut W,
+    ) -> io::Result<Result<(), String>> {
+        let numbers = |from: usize| -> Result<Vec<i64>, String> {
+            words[from..]
+                .iter()
+                .map(|word| {
+                    word.parse().map_err(|_| format!("not a number: {}", word))
+                })
+                .collect()
+        };
+
+        match words {
+            [] => {}
+            ["step"] | ["s"] => self.step(1, out)?,
+            ["step", _] | ["s", _] => match numbers(1) {
+                Ok(ref count) if count[0] < 0 => {
+                    return Ok(Err(format!("negative count: {}", count[0])))
+                }
+                Ok(count) => self.step(count[0], out)?,
+                Err(message) => return Ok(Err(message)),
+            },
+            ["continue"] | ["c"] => self.step(i64::MAX, out)?,
+            ["break", "op", opcode] => match self.parse_opcode(opcode) {
+                Some(opcode) => {
+                    self.opcode_breakpoints.insert(opcode);
+                }
+                None => return Ok(Err(format!("unknown opcode: {}", opcode))),
+            },
+            ["break", _] => match numbers(1) {
+                Ok(address) => {
+                    self.breakpoints.insert(address[0]);
+                }
+                Err(message) => return Ok(Err(message)),
+            },
+            ["watch", _] => match numbers(1) {
+                Ok(address) => {
+                    self.watchpoints.insert(address[0]);
+                }
+                Err(message) => return Ok(Err(message)),
+            },
+            ["delete"] => {
+                self.breakpoints.clear();
+                self.opcode_breakpoints.clear();
+                self.watchpoints.clear();
+            }
+            ["input", ..] => match numbers(1) {
+                Ok(values) => self.inputs.extend(values),
+                Err(message) => return Ok(Err(message)),
+            },
+            ["print", _] | ["print", _, _] | ["p", _] | ["p", _, _] => {
+                let arguments = match numbers(1) {
+                    Ok(arguments) => arguments,
+                    Err(message) => return Ok(Err(message)),
+                };
+                let start = arguments[0];
+                let count = *arguments.get(1).unwrap_or(&1);
+                if start < 0 {
+                    return Ok(Err(format!("negative address: {}", start)));
+                }
+                if count < 0 {
+                    return Ok(Err(format!("negative count: {}", count)));
+                }
+                // stop at the last address rather than overflowing past it
+                let addresses =
+                    (0..count).map_while(|index| start.checked_add(index));
+                for address in addresses {
+                    writeln!(out, "[{}] = {}", address, self.program[address])?;
+                }
+            }
+            ["set", _, _] => match numbers(1) {
+                Ok(ref arguments) if arguments[0] >= 0 => {
+                    self.program.set(arguments[0], arguments[1]);
+                }
+                Ok(arguments) => {
+                    return Ok(Err(format!(
+                        "negative address: {}",
+                        arguments[0]
+                    )))
+                }
+                Err(message) => return Ok(Err(message)),
+            },
+            ["regs"] => {
+                let state = &self.state;
+                writeln!(
+                    out,
+                    "ip = {}, rb = {}, cycles = {}",
+                    state.ip, state.relative_base, state.cycles
+                )?;
+            }
+            ["list"] => self.list(self.state.ip, 5, out)?,
+            ["list", _] => match numbers(1) {
+                Ok(ref count) if count[0] < 0 => {
+                    return Ok(Err(format!("negative count: {}", count[0])))
+                }
+                Ok(count) => self.list(self.state.ip, count[0], out)?,
+                Err(message) => return Ok(Err(message)),
+            },
+            ["help"] => writeln!(out, "{}", HELP)?,
+            _ => {
+                return Ok(Err(format!(
+                    "unknown command: {}, try help",
+                    words.join(" ")
+                )))
+            }
+        }
+        Ok(Ok(()))
+    }
+
+    /// Execute up to `count` instructions, stopping early for breakpoints,
+    /// watchpoints, a halt or a missing input
+    fn step<W: Write>(&mut self, count: i64, out: &mut W) -> io::Result<()> {
+        let mut executed = 0;
+        let stop = loop {
+            if executed == count {
+                break None;
+            }
+            if executed > 0 && self.at_breakpoint() {
+                break Some(Stop::Breakpoint);
+            }
+            let stop = self.execute(out)?;
+            if stop.is_some() {
+                break stop;
+            }
+            executed += 1;
+        };
+
+        match stop {
+            Some(Stop::Breakpoint) => writeln!(out, "breakpoint")?,
+            Some(Stop::Watchpoint(address, old, new)) => {
+                writeln!(out, "watch [{}]: {} -> {}", address, old, new)?
+            }
+            Some(Stop::Halted) => writeln!(out, "halted")?,
+            Some(Stop::WaitingForInput) => writeln!(out, "waiting for input")?,
+            Some(Stop::Fault(message)) => writeln!(out, "fault: {}", message)?,
+            None => {}
+        }
+        self.show_position(out)
+    }
+
+    /// Execute a single instruction
+    fn execute<W: Write>(&mut self, out: &mut W) -> io::Result<Option<Stop>> {
+        if self.state.status == ExitStatus::Halted {
+            return Ok(Some(Stop::Halted));
+        }
+
+        let watched: Vec<_> = self
+            .watchpoints
+            .iter()
+            .filter(|&&address| address >= 0)
+            .map(|&address| (address, self.program[address]))
+            .collect();
+
+        let inputs = &mut self.inputs;
+        let mut source = || inputs.pop_front();
+        let mut outputs = Vec::new();
+        let result = execute_instruction(
+            &mut self.program,
+            &mut self.state.ip,
+            &mut self.state.relative_base,
+            &mut source,
+            &mut outputs,
+            &mut Cache::disabled(),
+            self.instructions,
+        );
+        for output in outputs {
+            writeln!(out, "output: {}", output)?;
+        }
+
+        let stop = match result {
+            Ok(Some(ExitStatus::WaitingForInput)) => {
+                return Ok(Some(Stop::WaitingForInput));
+            }
+            Ok(Some(ExitStatus::Halted)) => {
+                self.state.status = ExitStatus::Halted;
+                Some(Stop::Halted)
+            }
+            Ok(_) => watched
+                .into_iter()
+                .find(|&(address, old)| self.program[address] != old)
+                .map(|(address, old)| {
+                    Stop::Watchpoint(address, old, self.program[address])
+                }),
+            // the instruction is tried again on the next step, so memory can
+            // be fixed in between
+            Err(fault) => {
+                let error = fault.at(self.state.ip, self.state.relative_base);
+                self.state.status = ExitStatus::Faulted(error.clone());
+                return Ok(Some(Stop::Fault(error.to_string())));
+            }
+        };
+        self.state.cycles += 1;
+        Ok(stop)
+    }
+
+    fn at_breakpoint(&self) -> bool {
+        let ip = self.state.ip;
+        self.breakpoints.contains(&ip)
+            || (ip >= 0
+                && self.opcode_breakpoints.contains(&(self.program[ip] % 100)))
+    }
+
+    fn show_position<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        if self.state.status != ExitStatus::Halted {
+            self.list(self.state.ip, 1, out)?;
+        }
+        Ok(())
+    }
+
+    fn list<W: Write>(
+        &self,
+        address: i64,
+        count: i64,
+        out: &mut W,
+    ) -> io::Result<()> {
+        let mut next = Some(address);
+        for _ in 0..count {
+            let address = match next {
+                Some(address) if address >= 0 => address,
+                _ => break,
+            };
+            let decoded =
+                disasm::decode(&self.program, address, self.instructions);
+            let (line, length) = match decoded {
+                Some(instruction) => {
+                    let length = instruction.len() as i64;
+                    (Line::Instruction(address, instruction), length)
+                }
+                None => (Line::Data(address, vec![self.program[address]]), 1),
+            };
+            writeln!(out, "{}", line)?;
+            next = address.checked_add(length);
+        }
+        Ok(())
+    }
+
+    fn parse_opcode(&self, text: &str) -> Option<i64> {
+        self.instructions
+            .iter()
+            .find(|operation| {
+                operation.mnemonic.eq_ignore_ascii_case(text)
+                    || operation.opcode.to_string() == text
+            })
+            .map(|operation| operation.opcode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::isa::{EXTENDED, STANDARD};
+
+    fn debug(program: Program, script: &str) -> String {
+        let mut out = Vec::new();
+        Debugger::new(program, &STANDARD)
+            .run(script.as_bytes(), &mut out)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_step() {
+        let program = program![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
+
+        assert_eq!(
+            debug(program, "step\nregs\nstep\nstep\n"),
+            "    0: ADD [9], [10], [3]\n\
+             \x20   4: MUL [3], [11], [0]\n\
+             ip = 4, rb = 0, cycles = 1\n\
+             \x20   8: HLT\n\
+             halted\n"
+        );
+    }
+
+    #[test]
+    fn test_break_on_address() {
+        let program = program![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
+
+        assert_eq!(
+            debug(program, "break 8\ncontinue\nprint 0 2\n"),
+            "    0: ADD [9], [10], [3]\n\
+             breakpoint\n\
+             \x20   8: HLT\n\
+             [0] = 3500\n\
+             [1] = 9\n"
+        );
+    }
+
+    #[test]
+    fn test_break_on_opcode() {
+        let program = program![1101, 1, 2, 9, 104, 7, 4, 9, 99, 0];
+
+        assert_eq!(
+            debug(program, "break op OUT\nc\nc\nc\n"),
+            "    0: ADD #1, #2, [9]\n\
+             breakpoint\n\
+             \x20   4: OUT #7\n\
+             output: 7\n\
+             breakpoint\n\
+             \x20   6: OUT [9]\n\
+             output: 3\n\
+             halted\n"
+        );
+    }
+
+    #[test]
+    fn test_watchpoint() {
+        let program = program![1101, 1, 2, 9, 1101, 0, 0, 10, 99, 0, 0];
+
+        assert_eq!(
+            debug(program, "watch 9\ncontinue\ncontinue\n"),
+            "    0: ADD #1, #2, [9]\n\
+             watch [9]: 0 -> 3\n\
+             \x20   4: ADD #0, #0, [10]\n\
+             halted\n"
+        );
+    }
+
+    #[test]
+    fn test_input_and_set() {
+        let program = program![3, 0, 4, 0, 99];
+
+        assert_eq!(
+            debug(program, "c\ninput 5\nstep\nset 0 42\nc\n"),
+            "    0: IN [0]\n\
+             waiting for input\n\
+             \x20   0: IN [0]\n\
+             \x20   2: OUT [0]\n\
+             output: 42\n\
+             halted\n"
+        );
+    }
+
+    #[test]
+    fn test_fault() {
+        let program = program![109, 2, 42];
+
+        assert_eq!(
+            debug(program, "c\n"),
+            "    0: ARB #2\n\
+             fault: unknown opcode 42 at position 2 (base 2)\n\
+             \x20   2: data 42\n"
+        );
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        assert_eq!(
+            debug(program![99], "jump\nquit\nstep\n"),
+            "    0: HLT\nerror: unknown command: jump, try help\n"
+        );
+    }
+
+    #[test]
+    fn test_extended_instructions() {
+        let program = program![1122, 0, 5, 121, 7, 99];
+        let mut out = Vec::new();
+        Debugger::new(program, &EXTENDED)
+            .run("break op exit\nc\nc\n".as_bytes(), &mut out)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "    0: CHK #0, #5\n\
+             breakpoint\n\
+             \x20   3: EXIT #7\n\
+             output: 7\n\
+             halted\n"
+        );
+    }
+
+    #[test]
+    fn test_counts() {
+        assert_eq!(
+            debug(
+                program![1101, 1, 1, 5, 99],
+                "step -1\nlist -2\nprint 0 -3\n"
+            ),
+            "    0: ADD #1, #1, [5]\n\
+             error: negative count: -1\n\
+             error: negative count: -2\n\
+             error: negative count: -3\n"
+        );
+        assert_eq!(
+            debug(program![99], "print 9223372036854775806 5\nlist\n"),
+            "    0: HLT\n\
+             [9223372036854775806] = 0\n\
+             [9223372036854775807] = 0\n\
+             \x20   0: HLT\n\
+             \x20   1: data 0\n\
+             \x20   2: data 0\n\
+             \x20   3: data 0\n\
+             \x20   4: data 0\n"
+        );
+    }
+}
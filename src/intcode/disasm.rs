@@ -0,0 +1,256 @@
+use intcode::isa::InstructionSet;
+use intcode::Program;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operand {
+    Position(i64),
+    Immediate(i64),
+    Relative(i64),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Operand::Position(address) => write!(f, "[{}]", address),
+            Operand::Immediate(value) => write!(f, "#{}", value),
+            Operand::Relative(offset) if offset < 0 => {
+                write!(f, "rb{}", offset)
+            }
+            Operand::Relative(offset) => write!(f, "rb+{}", offset),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Instruction {
+    pub mnemonic: &'static str,
+    pub opcode: i64,
+    pub operands: Vec<Operand>,
+}
+
+impl Instruction {
+    /// Amount of memory cells the instruction occupies
+    pub fn len(&self) -> usize {
+        self.operands.len() + 1
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.mnemonic)?;
+        for (index, operand) in self.operands.iter().enumerate() {
+            let separator = if index == 0 { " " } else { ", " };
+            write!(f, "{}{}", separator, operand)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single entry of a listing
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Line {
+    Instruction(i64, Instruction),
+    Data(i64, Vec<i64>),
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Line::Instruction(address, instruction) => {
+                write!(f, "{:>5}: {}", address, instruction)
+            }
+            Line::Data(address, values) => {
+                let values: Vec<_> =
+                    values.iter().map(|value| value.to_string()).collect();
+                write!(f, "{:>5}: data {}", address, values.join(", "))
+            }
+        }
+    }
+}
+
+/// Decode the instruction at the given address, if it is a valid one of the
+/// instruction set
+pub fn decode(
+    program: &Program,
+    address: i64,
+    instructions: &InstructionSet,
+) -> Option<Instruction> {
+    if address < 0 {
+        return None;
+    }
+    let value = program.get(address);
+    let opcode = value % 100;
+    let operation = instructions.get(opcode)?;
+    let (mnemonic, write) = (operation.mnemonic, operation.write);
+
+    let mut modes = value / 100;
+    let mut operands = Vec::new();
+    for index in 0..operation.arity {
+        let parameter = program.get(address.checked_add(1 + index as i64)?);
+        let operand = match modes % 10 {
+            0 => Operand::Position(parameter),
+            1 if write != Some(index) => Operand::Immediate(parameter),
+            2 => Operand::Relative(parameter),
+            _ => return None,
+        };
+        operands.push(operand);
+        modes /= 10;
+    }
+    // modes for parameters the instruction does not have
+    if modes != 0 {
+        return None;
+    }
+
+    Some(Instruction { mnemonic, opcode, operands })
+}
+
+/// Disassemble a program by sweeping through it from address 0
+///
+/// Cells that do not form a valid instruction are collected in data lines.
+pub fn disassemble(
+    program: &Program,
+    instructions: &InstructionSet,
+) -> Vec<Line> {
+    let end = program.len() as i64;
+    let mut lines = Vec::new();
+    let mut address = 0;
+    while address < end {
+        match decode(program, address, instructions) {
+            Some(ref instruction)
+                if address + instruction.len() as i64 <= end =>
+            {
+                let length = instruction.len() as i64;
+                lines.push(Line::Instruction(address, instruction.clone()));
+                address += length;
+            }
+            _ => {
+                let value = program.get(address);
+                match lines.last_mut() {
+                    Some(Line::Data(_, ref mut values)) => values.push(value),
+                    _ => lines.push(Line::Data(address, vec![value])),
+                }
+                address += 1;
+            }
+        }
+    }
+    lines
+}
+
+/// Disassemble a program into a printable listing
+pub fn listing(program: &Program, instructions: &InstructionSet) -> String {
+    disassemble(program, instructions)
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::isa::{EXTENDED, STANDARD};
+
+    #[test]
+    fn test_decode_modes() {
+        let program = program![21101, 5, -3, 7];
+
+        assert_eq!(
+            decode(&program, 0, &STANDARD),
+            Some(Instruction {
+                mnemonic: "ADD",
+                opcode: 1,
+                operands: vec![
+                    Operand::Immediate(5),
+                    Operand::Immediate(-3),
+                    Operand::Relative(7),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_immediate_write() {
+        let program = program![11101, 1, 1, 0];
+
+        assert_eq!(decode(&program, 0, &STANDARD), None);
+    }
+
+    #[test]
+    fn test_decode_unknown_opcode() {
+        let program = program![42];
+
+        assert_eq!(decode(&program, 0, &STANDARD), None);
+    }
+
+    #[test]
+    fn test_operand_display() {
+        let operands = [
+            Operand::Position(12),
+            Operand::Immediate(5),
+            Operand::Relative(3),
+            Operand::Relative(-1),
+        ];
+        let displayed: Vec<_> =
+            operands.iter().map(|operand| operand.to_string()).collect();
+
+        assert_eq!(displayed, vec!["[12]", "#5", "rb+3", "rb-1"]);
+    }
+
+    #[test]
+    fn test_listing() {
+        let program = program![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
+
+        let expected = [
+            "    0: ADD [9], [10], [3]",
+            "    4: MUL [3], [11], [0]",
+            "    8: HLT",
+            "    9: data 30, 40, 50",
+        ];
+
+        assert_eq!(
+            listing(&program, &STANDARD).lines().collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_listing_relative_mode() {
+        let program = program![109, 1, 204, -1, 99];
+
+        assert_eq!(
+            listing(&program, &STANDARD),
+            "    0: ARB #1\n    2: OUT rb-1\n    4: HLT\n"
+        );
+    }
+
+    #[test]
+    fn test_disassemble_truncated_instruction() {
+        let program = program![99, 1, 0];
+
+        assert_eq!(
+            disassemble(&program, &STANDARD),
+            vec![
+                Line::Instruction(
+                    0,
+                    Instruction {
+                        mnemonic: "HLT",
+                        opcode: 99,
+                        operands: vec![]
+                    }
+                ),
+                Line::Data(1, vec![1, 0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_listing_extended() {
+        let program = program![1122, 0, 5, 21, 7, 99];
+
+        assert_eq!(
+            listing(&program, &EXTENDED),
+            "    0: CHK #0, #5\n    3: EXIT [7]\n    5: HLT\n"
+        );
+        assert!(listing(&program, &STANDARD).starts_with("    0: data 1122"));
+    }
+}
@@ -0,0 +1,360 @@
+//! Property and differential tests on randomly generated programs
+//!
+//! Every program is well-formed: it starts by moving the relative base to its
+//! data, only uses valid modes, only writes to its data and only jumps to its
+//! own instructions. Programs without loops always terminate, programs with
+//! loops run under an instruction budget. Self-modifying programs also write
+//! to their own code, which may turn it into anything. Set `INTCODE_FUZZ_CASES` to run more
+//! cases than the default.
+
+use intcode::cell::Checked;
+use intcode::isa::{EXTENDED, STANDARD};
+use intcode::snapshot::{load_snapshot, save_snapshot, Format};
+use intcode::{
+    dump, io, load_as, start, Cell, Config, ExitStatus, IntcodeError, Limits,
+    MachineState, Memory, Program, Runner, Step,
+};
+use num::BigInt;
+use std::env;
+use std::fmt;
+
+const CASES: u64 = 300;
+const BUDGET: u64 = 2_000;
+/// Amount of data cells after the code
+const DATA: i64 = 16;
+
+/// xorshift64*, good enough to generate programs and reproducible by seed
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number in `low..high`
+    fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next() % (high - low) as u64) as i64
+    }
+}
+
+/// A generated program and the inputs it is run with
+struct Case {
+    seed: u64,
+    program: Program,
+    inputs: Vec<i64>,
+}
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "seed {}: program {} inputs {:?}",
+            self.seed,
+            dump(&self.program),
+            self.inputs
+        )
+    }
+}
+
+impl Case {
+    fn generate(seed: u64, loops: bool) -> Case {
+        Case::generate_as(seed, loops, false)
+    }
+
+    /// A program with loops whose writes sometimes land on its own code
+    fn self_modifying(seed: u64) -> Case {
+        Case::generate_as(seed, true, true)
+    }
+
+    fn generate_as(seed: u64, loops: bool, self_modifying: bool) -> Case {
+        let mut rng = Rng::new(seed);
+        let count = rng.range(1, 40) as usize;
+        let opcodes: Vec<i64> = (0..count).map(|_| rng.range(1, 10)).collect();
+
+        // the instructions start after moving the base, the halt comes last
+        let mut addresses = vec![2];
+        for &opcode in &opcodes {
+            let arity = STANDARD.get(opcode).unwrap().arity as i64;
+            addresses.push(addresses.last().unwrap() + 1 + arity);
+        }
+        let data = addresses[count] + 1;
+
+        let mut cells = vec![109, data];
+        for (index, &opcode) in opcodes.iter().enumerate() {
+            let operation = STANDARD.get(opcode).unwrap();
+            let mut modes = 0;
+            let mut params = Vec::new();
+            for param in 0..operation.arity {
+                let (mode, value) = if operation.write == Some(param) {
+                    match rng.range(0, 2) {
+                        0 if self_modifying && rng.range(0, 3) == 0 => {
+                            (0, rng.range(2, data))
+                        }
+                        0 => (0, data + rng.range(0, DATA)),
+                        _ => (2, rng.range(0, DATA)),
+                    }
+                } else if (opcode == 5 || opcode == 6) && param == 1 {
+                    let first = if loops { 0 } else { index as i64 + 1 };
+                    (1, addresses[rng.range(first, count as i64 + 1) as usize])
+                } else if opcode == 9 {
+                    // the base never drops below the data
+                    (1, rng.range(0, 4))
+                } else {
+                    match rng.range(0, 3) {
+                        0 => (0, data + rng.range(0, DATA)),
+                        1 => (1, rng.range(-9, 10)),
+                        _ => (2, rng.range(0, DATA)),
+                    }
+                };
+                modes += mode * 10i64.pow(param as u32);
+                params.push(value);
+            }
+            cells.push(opcode + 100 * modes);
+            cells.extend(params);
+        }
+        cells.push(99);
+        cells.extend((0..DATA).map(|_| rng.range(-9, 10)));
+
+        let inputs = (0..rng.range(0, 6)).map(|_| rng.range(-9, 10)).collect();
+        Case { seed, program: Program::from(cells), inputs }
+    }
+}
+
+/// Check a property on generated programs, with or without loops
+fn check<F: Fn(&Case)>(loops: bool, property: F) {
+    check_cases(|seed| Case::generate(seed, loops), property);
+}
+
+fn check_cases<G, F>(generate: G, property: F)
+where
+    G: Fn(u64) -> Case,
+    F: Fn(&Case),
+{
+    let cases = env::var("INTCODE_FUZZ_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(CASES);
+    for seed in 0..cases {
+        property(&generate(seed));
+    }
+}
+
+fn budget(instructions: u64) -> Limits {
+    Limits { max_instructions: Some(instructions), ..Limits::none() }
+}
+
+/// Run with the budget, returning the runner and every output
+fn run_budgeted<C: Cell>(
+    program: Memory<C>,
+    inputs: Vec<C>,
+) -> (Result<Runner<C>, IntcodeError>, Vec<C>) {
+    let mut outputs = Vec::new();
+    let result = Runner::new(program).run_limited(
+        &mut io::iter(inputs),
+        &mut outputs,
+        &budget(BUDGET),
+    );
+    (result, outputs)
+}
+
+/// Where a run ended up, in a form that can be compared between cell types
+type Outcome = (Result<MachineState, IntcodeError>, Vec<String>, Vec<String>);
+
+fn outcome<C: Cell>(case: &Case) -> Outcome {
+    let convert = |value: i64| C::from_i64(value);
+    let program = load_as(&dump(&case.program));
+    let inputs = case.inputs.iter().cloned().map(convert).collect();
+
+    let (result, outputs) = run_budgeted::<C>(program, inputs);
+    let text = |values: &mut dyn Iterator<Item = C>| {
+        values.map(|value| value.to_string()).collect::<Vec<_>>()
+    };
+    let memory = match result {
+        Ok(ref runner) => runner
+            .program
+            .ranges()
+            .into_iter()
+            .map(|(start, cells)| {
+                format!(
+                    "{}:{}",
+                    start,
+                    text(&mut cells.iter().cloned()).join(",")
+                )
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    let state = result.map(|runner| runner.state);
+    (state, text(&mut outputs.into_iter()), memory)
+}
+
+fn overflowed(outcome: &Outcome) -> bool {
+    matches!(
+        outcome.0,
+        Ok(MachineState {
+            status: ExitStatus::Faulted(IntcodeError::Overflow { .. }),
+            ..
+        })
+    )
+}
+
+#[test]
+fn test_generated_programs_are_well_formed() {
+    check(true, |case| {
+        let (result, _) =
+            run_budgeted(case.program.clone(), case.inputs.clone());
+        assert!(result.is_ok(), "{}: {:?}", case, result);
+    });
+}
+
+#[test]
+fn test_deterministic() {
+    check(true, |case| {
+        assert_eq!(
+            run_budgeted(case.program.clone(), case.inputs.clone()),
+            run_budgeted(case.program.clone(), case.inputs.clone()),
+            "{}",
+            case
+        );
+    });
+}
+
+#[test]
+fn test_snapshot_resume() {
+    check(true, |case| {
+        let (expected, expected_outputs) =
+            run_budgeted(case.program.clone(), case.inputs.clone());
+
+        // stop somewhere, save, load and continue with what is left
+        let split = Rng::new(!case.seed).range(0, BUDGET as i64) as u64;
+        let format = match split % 2 {
+            0 => Format::Binary,
+            _ => Format::Text,
+        };
+        let mut source = io::iter(case.inputs.clone());
+        let mut outputs = Vec::new();
+        let mut runner = Runner::new(case.program.clone())
+            .run_limited(&mut source, &mut outputs, &budget(split))
+            .unwrap();
+        if let ExitStatus::LimitReached(_) = runner.state.status {
+            let mut bytes = Vec::new();
+            save_snapshot(&runner, &mut bytes, format).unwrap();
+            runner = load_snapshot(&bytes[..]).unwrap();
+            runner = runner
+                .run_limited(&mut source, &mut outputs, &budget(BUDGET - split))
+                .unwrap();
+        }
+
+        assert_eq!(Ok(runner), expected, "{} split at {}", case, split);
+        assert_eq!(outputs, expected_outputs, "{} split at {}", case, split);
+    });
+}
+
+#[test]
+fn test_step_in_pieces_matches_steps() {
+    check(false, |case| {
+        let started = start(case.program.clone()).unwrap();
+        if started.state.status == ExitStatus::Halted {
+            return;
+        }
+        let mut outputs = started.outputs.clone();
+        let batch = started.clone().steps(case.inputs.clone()).unwrap();
+        outputs.extend(batch.outputs.clone());
+
+        let mut pieces = started.clone();
+        let mut piece_outputs = started.outputs.clone();
+        for &input in &case.inputs {
+            if pieces.state.status == ExitStatus::Halted {
+                break;
+            }
+            pieces = pieces.step(input).unwrap();
+            piece_outputs.extend(pieces.outputs.clone());
+        }
+
+        assert_eq!(pieces.program, batch.program, "{}", case);
+        assert_eq!(pieces.state, batch.state, "{}", case);
+        assert_eq!(piece_outputs, outputs, "{}", case);
+    });
+}
+
+#[test]
+fn test_cached_matches_uncached() {
+    check(false, |case| {
+        let runner = Runner::new(case.program.clone());
+        let (mut cached, mut uncached) = (Vec::new(), Vec::new());
+
+        let cached_runner =
+            runner.clone().run(&mut io::iter(case.inputs.clone()), &mut cached);
+        let uncached_runner = runner
+            .run_uncached(&mut io::iter(case.inputs.clone()), &mut uncached);
+
+        assert_eq!(cached_runner, uncached_runner, "{}", case);
+        assert_eq!(cached, uncached, "{}", case);
+    });
+}
+
+#[test]
+fn test_self_modifying_cached_matches_uncached() {
+    check_cases(Case::self_modifying, |case| {
+        let run = |cached: bool| {
+            let mut outputs = Vec::new();
+            let config =
+                Config { limits: budget(BUDGET), cached, ..Config::new() };
+            let result = Runner::new(case.program.clone()).execute(
+                &mut io::iter(case.inputs.clone()),
+                &mut outputs,
+                &mut (),
+                &mut |_: &Vec<i64>| false,
+                config,
+            );
+            (result, outputs)
+        };
+
+        assert_eq!(run(true), run(false), "{}", case);
+    });
+}
+
+#[test]
+fn test_extended_matches_standard() {
+    check(false, |case| {
+        let runner = Runner::new(case.program.clone());
+        let (mut standard, mut extended) = (Vec::new(), Vec::new());
+
+        let standard_runner = runner
+            .clone()
+            .run(&mut io::iter(case.inputs.clone()), &mut standard);
+        let extended_runner = runner.run_with(
+            &mut io::iter(case.inputs.clone()),
+            &mut extended,
+            &EXTENDED,
+        );
+
+        assert_eq!(standard_runner, extended_runner, "{}", case);
+        assert_eq!(standard, extended, "{}", case);
+    });
+}
+
+#[test]
+fn test_cell_types_agree() {
+    check(true, |case| {
+        let checked = outcome::<Checked>(case);
+        let wide = outcome::<i128>(case);
+        let big = outcome::<BigInt>(case);
+
+        // wider cells only differ from plain ones once those overflow
+        if !overflowed(&checked) {
+            assert_eq!(outcome::<i64>(case), checked, "{}", case);
+            assert_eq!(wide, checked, "{}", case);
+        }
+        if !overflowed(&wide) {
+            assert_eq!(big, wide, "{}", case);
+        }
+    });
+}
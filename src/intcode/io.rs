@@ -0,0 +1,120 @@
+use intcode::{Input, Output};
+use std::sync::mpsc;
+
+/// Where a running Intcode program gets its inputs from
+///
+/// Returning `None` means no input is available right now, which suspends the
+/// program with `ExitStatus::WaitingForInput`.
+pub trait Source<C = Input> {
+    fn read(&mut self) -> Option<C>;
+}
+
+/// Where a running Intcode program sends its outputs to, as they happen
+pub trait Sink<C = Output> {
+    fn write(&mut self, output: C);
+}
+
+impl<C, F: FnMut() -> Option<C>> Source<C> for F {
+    fn read(&mut self) -> Option<C> {
+        self()
+    }
+}
+
+/// Blocks until an input arrives, runs dry once all senders are gone
+impl<C> Source<C> for mpsc::Receiver<C> {
+    fn read(&mut self) -> Option<C> {
+        self.recv().ok()
+    }
+}
+
+impl<C, F: FnMut(C)> Sink<C> for F {
+    fn write(&mut self, output: C) {
+        self(output)
+    }
+}
+
+impl<C> Sink<C> for Vec<C> {
+    fn write(&mut self, output: C) {
+        self.push(output);
+    }
+}
+
+/// Outputs sent after the receiver is gone are dropped
+impl<C> Sink<C> for mpsc::Sender<C> {
+    fn write(&mut self, output: C) {
+        self.send(output).ok();
+    }
+}
+
+impl<C> Sink<C> for mpsc::SyncSender<C> {
+    fn write(&mut self, output: C) {
+        self.send(output).ok();
+    }
+}
+
+/// Turn anything iterable into a source that is consumed lazily
+pub fn iter<C, I>(inputs: I) -> impl Source<C>
+where
+    I: IntoIterator<Item = C>,
+{
+    let mut inputs = inputs.into_iter();
+    move || inputs.next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closure_source() {
+        let mut count = 0;
+        let mut source = || {
+            count += 1;
+            Some(count)
+        };
+
+        assert_eq!(source.read(), Some(1));
+        assert_eq!(source.read(), Some(2));
+    }
+
+    #[test]
+    fn test_iter_source() {
+        let mut source = iter((1..3).map(|input| input * 10));
+
+        assert_eq!(source.read(), Some(10));
+        assert_eq!(source.read(), Some(20));
+        assert_eq!(source.read(), None);
+    }
+
+    #[test]
+    fn test_channel_source_runs_dry() {
+        let (sender, mut receiver) = mpsc::channel();
+        sender.send(5).unwrap();
+        drop(sender);
+
+        assert_eq!(receiver.read(), Some(5));
+        assert_eq!(receiver.read(), None);
+    }
+
+    #[test]
+    fn test_closure_sink() {
+        let mut received = Vec::new();
+        {
+            let mut sink = |output| received.push(output * 2);
+            sink.write(1);
+            sink.write(2);
+        }
+
+        assert_eq!(received, vec![2, 4]);
+    }
+
+    #[test]
+    fn test_channel_sink() {
+        let (mut sender, receiver) = mpsc::channel();
+        sender.write(3);
+        sender.write(4);
+        drop(sender);
+
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), vec![3, 4]);
+    }
+}
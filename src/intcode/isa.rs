@@ -0,0 +1,418 @@
+use intcode::{Cell, Memory, Sink, Source};
+
+/// Carries out an instruction whose parameters were resolved already
+pub type Handler<C = i64> = fn(&mut Context<C>) -> Flow;
+
+/// A single instruction of an instruction set
+#[derive(Debug)]
+pub struct Operation<C: Cell = i64> {
+    pub mnemonic: &'static str,
+    pub opcode: i64,
+    /// Amount of parameters, at most 3
+    pub arity: usize,
+    /// Index of the parameter that is written to
+    pub write: Option<usize>,
+    pub handler: Handler<C>,
+}
+
+// not derived, that would require cells to be `Copy`
+impl<C: Cell> Clone for Operation<C> {
+    fn clone(&self) -> Operation<C> {
+        *self
+    }
+}
+
+impl<C: Cell> Copy for Operation<C> {}
+
+/// What the machine does after an instruction
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Flow {
+    /// Continue with the instruction after this one
+    Next,
+    /// Continue at the given address
+    Jump(i64),
+    /// An input is needed that is not available yet, the instruction is
+    /// executed again once the program is resumed
+    Wait,
+    Halt,
+    /// Stop the program with `IntcodeError::Trap`
+    Trap(String),
+    /// Stop the program with `IntcodeError::Overflow`
+    Overflow,
+}
+
+/// The parameters of an instruction and the machine executing it
+pub struct Context<'a, C: Cell = i64> {
+    /// Values of the parameters that are read, with their modes applied, the
+    /// parameter that is written to is left at 0
+    pub params: [C; 3],
+    /// Address of the instruction
+    pub position: i64,
+    /// Changes are kept when the instruction does not wait, halt or trap
+    pub base: i64,
+    /// Address of the parameter that is written to
+    pub(super) write: Option<i64>,
+    pub(super) program: &'a mut Memory<C>,
+    pub(super) source: &'a mut dyn Source<C>,
+    pub(super) sink: &'a mut dyn Sink<C>,
+    pub(super) output: bool,
+}
+
+impl<'a, C: Cell> Context<'a, C> {
+    /// Write to the address of the write parameter
+    pub fn store(&mut self, value: C) {
+        let address = self.write.expect("instruction has no write parameter");
+        self.program.set(address, value);
+    }
+
+    pub fn input(&mut self) -> Option<C> {
+        self.source.read()
+    }
+
+    /// Produce an output, a run that pauses on outputs stops after this
+    /// instruction
+    pub fn output(&mut self, value: C) {
+        self.sink.write(value);
+        self.output = true;
+    }
+}
+
+/// The instructions of the finished Intcode computer
+pub fn operations<C: Cell>() -> [Operation<C>; 10] {
+    [
+        operation("ADD", 1, 3, Some(2), add),
+        operation("MUL", 2, 3, Some(2), multiply),
+        operation("IN", 3, 1, Some(0), input),
+        operation("OUT", 4, 1, None, output),
+        operation("JT", 5, 2, None, jump_if_true),
+        operation("JF", 6, 2, None, jump_if_false),
+        operation("LT", 7, 3, Some(2), less_than),
+        operation("EQ", 8, 3, Some(2), equals),
+        operation("ARB", 9, 1, None, adjust_base),
+        operation("HLT", 99, 0, None, halt),
+    ]
+}
+
+/// Instructions for programs written by hand, the puzzles never use them
+///
+/// `DBG` prints its parameter to stderr, `EXIT` outputs its parameter as an
+/// exit code and halts, `CHK` traps unless its first parameter is an address
+/// below its second one.
+pub fn extensions<C: Cell>() -> [Operation<C>; 3] {
+    [
+        operation("DBG", 20, 1, None, debug),
+        operation("EXIT", 21, 1, None, exit),
+        operation("CHK", 22, 2, None, check),
+    ]
+}
+
+/// A set of the standard operations, prefer `Cell::instructions` which does
+/// not build a new one every time
+pub fn standard<C: Cell>() -> InstructionSet<C> {
+    InstructionSet::new().with_all(&operations())
+}
+
+lazy_static! {
+    /// The instruction set programs run with unless told otherwise
+    pub static ref STANDARD: InstructionSet = standard();
+    /// The standard instruction set and the extensions
+    pub static ref EXTENDED: InstructionSet = standard().with_all(&extensions());
+}
+
+fn operation<C: Cell>(
+    mnemonic: &'static str,
+    opcode: i64,
+    arity: usize,
+    write: Option<usize>,
+    handler: Handler<C>,
+) -> Operation<C> {
+    Operation { mnemonic, opcode, arity, write, handler }
+}
+
+/// Opcodes are the last two digits of an instruction
+const OPCODES: usize = 100;
+
+/// The operations a machine knows, by opcode
+///
+/// Executing an opcode that is not in the set faults with
+/// `IntcodeError::UnknownOpcode`.
+#[derive(Debug, Clone)]
+pub struct InstructionSet<C: Cell = i64> {
+    operations: Vec<Option<Operation<C>>>,
+}
+
+impl<C: Cell> InstructionSet<C> {
+    /// A set without any operations
+    pub fn new() -> InstructionSet<C> {
+        InstructionSet { operations: vec![None; OPCODES] }
+    }
+
+    /// Add an operation, replacing the one with the same opcode
+    pub fn with(mut self, operation: Operation<C>) -> InstructionSet<C> {
+        assert!(
+            (0..OPCODES as i64).contains(&operation.opcode),
+            "opcode {} is not in 0..100",
+            operation.opcode
+        );
+        assert!(
+            operation.arity <= 3,
+            "{} has too many parameters",
+            operation.mnemonic
+        );
+        assert!(
+            operation.write.is_none_or(|index| index < operation.arity),
+            "{} writes to a parameter it does not have",
+            operation.mnemonic
+        );
+        self.operations[operation.opcode as usize] = Some(operation);
+        self
+    }
+
+    pub fn with_all(self, operations: &[Operation<C>]) -> InstructionSet<C> {
+        operations.iter().fold(self, |set, &operation| set.with(operation))
+    }
+
+    /// A dialect that only knows the given opcodes of this set
+    pub fn only(&self, opcodes: &[i64]) -> InstructionSet<C> {
+        opcodes
+            .iter()
+            .filter_map(|&opcode| self.get(opcode))
+            .fold(InstructionSet::new(), |set, &operation| set.with(operation))
+    }
+
+    pub fn get(&self, opcode: i64) -> Option<&Operation<C>> {
+        if opcode < 0 {
+            return None;
+        }
+        self.operations.get(opcode as usize)?.as_ref()
+    }
+
+    /// Look up an operation by its mnemonic, ignoring case
+    pub fn find(&self, mnemonic: &str) -> Option<&Operation<C>> {
+        self.iter()
+            .find(|operation| operation.mnemonic.eq_ignore_ascii_case(mnemonic))
+    }
+
+    /// The operations ordered by opcode
+    pub fn iter(&self) -> impl Iterator<Item = &Operation<C>> {
+        self.operations.iter().flatten()
+    }
+}
+
+impl<C: Cell> Default for InstructionSet<C> {
+    fn default() -> InstructionSet<C> {
+        InstructionSet::new()
+    }
+}
+
+fn add<C: Cell>(context: &mut Context<C>) -> Flow {
+    match context.params[0].add(&context.params[1]) {
+        Some(sum) => {
+            context.store(sum);
+            Flow::Next
+        }
+        None => Flow::Overflow,
+    }
+}
+
+fn multiply<C: Cell>(context: &mut Context<C>) -> Flow {
+    match context.params[0].mul(&context.params[1]) {
+        Some(product) => {
+            context.store(product);
+            Flow::Next
+        }
+        None => Flow::Overflow,
+    }
+}
+
+fn input<C: Cell>(context: &mut Context<C>) -> Flow {
+    match context.input() {
+        Some(input) => {
+            context.store(input);
+            Flow::Next
+        }
+        None => Flow::Wait,
+    }
+}
+
+fn output<C: Cell>(context: &mut Context<C>) -> Flow {
+    let value = context.params[0].clone();
+    context.output(value);
+    Flow::Next
+}
+
+fn jump_if_true<C: Cell>(context: &mut Context<C>) -> Flow {
+    match context.params[0] == C::default() {
+        true => Flow::Next,
+        false => jump(&context.params[1]),
+    }
+}
+
+fn jump_if_false<C: Cell>(context: &mut Context<C>) -> Flow {
+    match context.params[0] == C::default() {
+        true => jump(&context.params[1]),
+        false => Flow::Next,
+    }
+}
+
+fn jump<C: Cell>(target: &C) -> Flow {
+    target.to_i64().map_or(Flow::Overflow, Flow::Jump)
+}
+
+fn less_than<C: Cell>(context: &mut Context<C>) -> Flow {
+    let less = context.params[0] < context.params[1];
+    context.store(C::from_i64(less as i64));
+    Flow::Next
+}
+
+fn equals<C: Cell>(context: &mut Context<C>) -> Flow {
+    let equal = context.params[0] == context.params[1];
+    context.store(C::from_i64(equal as i64));
+    Flow::Next
+}
+
+fn adjust_base<C: Cell>(context: &mut Context<C>) -> Flow {
+    let base = context.params[0]
+        .to_i64()
+        .and_then(|offset| context.base.checked_add(offset));
+    match base {
+        Some(base) => {
+            context.base = base;
+            Flow::Next
+        }
+        None => Flow::Overflow,
+    }
+}
+
+fn halt<C: Cell>(_: &mut Context<C>) -> Flow {
+    Flow::Halt
+}
+
+fn debug<C: Cell>(context: &mut Context<C>) -> Flow {
+    eprintln!("{:>5}: {}", context.position, context.params[0]);
+    Flow::Next
+}
+
+fn exit<C: Cell>(context: &mut Context<C>) -> Flow {
+    let code = context.params[0].clone();
+    context.output(code);
+    Flow::Halt
+}
+
+fn check<C: Cell>(context: &mut Context<C>) -> Flow {
+    let (address, bound) = (&context.params[0], &context.params[1]);
+    if &C::default() <= address && address < bound {
+        return Flow::Next;
+    }
+    Flow::Trap(format!("address {} is not below {}", address, bound))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::{
+        io, ExitStatus, Input, IntcodeError, Output, Program, Runner,
+    };
+
+    fn run(
+        program: Program,
+        instructions: &InstructionSet,
+        inputs: Vec<Input>,
+    ) -> Result<(Runner, Vec<Output>), IntcodeError> {
+        let mut outputs = Vec::new();
+        let runner = Runner::new(program).run_with(
+            &mut io::iter(inputs),
+            &mut outputs,
+            instructions,
+        )?;
+        Ok((runner.or_fault()?, outputs))
+    }
+
+    #[test]
+    fn test_standard() {
+        assert_eq!(STANDARD.iter().count(), 10);
+        assert_eq!(STANDARD.get(9).unwrap().mnemonic, "ARB");
+        assert_eq!(STANDARD.find("mul").unwrap().opcode, 2);
+        assert!(STANDARD.get(-1).is_none());
+        assert!(STANDARD.get(10).is_none());
+    }
+
+    #[test]
+    fn test_restricted_dialect() {
+        let dialect = STANDARD.only(&[1, 2, 99]);
+
+        let (runner, _) =
+            run(program![1, 0, 0, 0, 2, 0, 0, 0, 99], &dialect, vec![])
+                .unwrap();
+        assert_eq!(runner.program[0], 4);
+
+        assert_eq!(
+            run(program![3, 0, 99], &dialect, vec![1]),
+            Err(IntcodeError::UnknownOpcode {
+                opcode: 3,
+                position: 0,
+                base: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_custom_operation() {
+        // negate the parameter, write to the second one
+        fn negate(context: &mut Context) -> Flow {
+            let negated = -context.params[0];
+            context.store(negated);
+            Flow::Next
+        }
+        let instructions = STANDARD.clone().with(Operation {
+            mnemonic: "NEG",
+            opcode: 42,
+            arity: 2,
+            write: Some(1),
+            handler: negate,
+        });
+
+        let (_, outputs) = run(
+            program![3, 9, 42, 9, 9, 4, 9, 99, 0, 0],
+            &instructions,
+            vec![5],
+        )
+        .unwrap();
+
+        assert_eq!(outputs, vec![-5]);
+        assert!(run(program![42, 0, 0, 99], &STANDARD, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_halt_with_code() {
+        let (runner, outputs) =
+            run(program![3, 7, 21, 7, 99, 3, 0], &EXTENDED, vec![5]).unwrap();
+
+        assert_eq!(runner.state.status, ExitStatus::Halted);
+        assert_eq!(runner.state.ip, 2);
+        assert_eq!(outputs, vec![5]);
+    }
+
+    #[test]
+    fn test_bounds_check() {
+        // check that the input is below 10
+        let program = program![3, 20, 1022, 20, 10, 99];
+
+        assert!(run(program.clone(), &EXTENDED, vec![4]).is_ok());
+        assert_eq!(
+            run(program, &EXTENDED, vec![11]),
+            Err(IntcodeError::Trap {
+                message: "address 11 is not below 10".to_string(),
+                position: 2,
+                base: 0,
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "opcode 100 is not in 0..100")]
+    fn test_opcode_out_of_range() {
+        let halt = *STANDARD.get(99).unwrap();
+        InstructionSet::new().with(Operation { opcode: 100, ..halt });
+    }
+}
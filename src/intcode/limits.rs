@@ -0,0 +1,139 @@
+use intcode::{Cell, Memory};
+use std::time::Instant;
+
+/// How often the clock is read, checking it on every instruction would slow
+/// down execution considerably
+const DEADLINE_INTERVAL: u64 = 1024;
+
+/// The limit that stopped a run
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Limit {
+    Instructions,
+    Memory,
+    Deadline,
+}
+
+/// Bounds on a single run of a program, a run that hits one of them stops
+/// with the `LimitReached` status and can be resumed
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Limits {
+    /// Amount of instructions a run may execute
+    pub max_instructions: Option<u64>,
+    /// Amount of memory cells the program may occupy, see
+    /// `Memory::footprint`
+    pub max_memory: Option<usize>,
+    /// Moment the run has to stop, it is checked every 1024 instructions
+    pub deadline: Option<Instant>,
+}
+
+impl Limits {
+    /// No limits at all
+    pub fn none() -> Limits {
+        Limits::default()
+    }
+
+    /// The limit that keeps the next instruction from executing, after
+    /// `executed` instructions in this run
+    pub(super) fn reached<C: Cell>(
+        &self,
+        program: &Memory<C>,
+        executed: u64,
+    ) -> Option<Limit> {
+        if self.max_instructions.is_some_and(|max| executed >= max) {
+            return Some(Limit::Instructions);
+        }
+        if self.max_memory.is_some_and(|max| program.footprint() > max) {
+            return Some(Limit::Memory);
+        }
+        match self.deadline {
+            Some(deadline)
+                if executed.is_multiple_of(DEADLINE_INTERVAL)
+                    && Instant::now() >= deadline =>
+            {
+                Some(Limit::Deadline)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::{io, ExitStatus, Program, Runner};
+    use std::time::Duration;
+
+    // jump back to itself forever
+    const LOOP: [i64; 3] = [1105, 1, 0];
+
+    fn run(program: Program, limits: Limits) -> Runner {
+        Runner::new(program)
+            .run_limited(&mut io::iter(vec![]), &mut Vec::new(), &limits)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_instruction_limit() {
+        let runner = run(
+            Program::from(LOOP.to_vec()),
+            Limits { max_instructions: Some(10), ..Limits::none() },
+        );
+
+        assert_eq!(
+            runner.state.status,
+            ExitStatus::LimitReached(Limit::Instructions)
+        );
+        assert_eq!(runner.state.cycles, 10);
+    }
+
+    #[test]
+    fn test_resume_after_limit() {
+        // count down from 20, then halt
+        let program = program![1001, 9, -1, 9, 1005, 9, 0, 99, 0, 20];
+        let limits = Limits { max_instructions: Some(25), ..Limits::none() };
+
+        let runner = run(program, limits);
+        assert_eq!(
+            runner.state.status,
+            ExitStatus::LimitReached(Limit::Instructions)
+        );
+        assert_eq!(runner.program[9], 7);
+
+        let runner = runner
+            .run_limited(&mut io::iter(vec![]), &mut Vec::new(), &limits)
+            .unwrap();
+        assert_eq!(runner.state.status, ExitStatus::Halted);
+        assert_eq!(runner.state.cycles, 41);
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        // write ever further away, the relative base grows by 1000 each time
+        let program = program![109, 1000, 21101, 1, 1, 0, 1105, 1, 0];
+
+        let runner =
+            run(program, Limits { max_memory: Some(10_000), ..Limits::none() });
+
+        assert_eq!(
+            runner.state.status,
+            ExitStatus::LimitReached(Limit::Memory)
+        );
+        assert!(runner.program.footprint() > 10_000);
+    }
+
+    #[test]
+    fn test_deadline() {
+        let runner = run(
+            Program::from(LOOP.to_vec()),
+            Limits {
+                deadline: Some(Instant::now() + Duration::from_millis(10)),
+                ..Limits::none()
+            },
+        );
+
+        assert_eq!(
+            runner.state.status,
+            ExitStatus::LimitReached(Limit::Deadline)
+        );
+    }
+}
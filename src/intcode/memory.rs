@@ -0,0 +1,257 @@
+use intcode::Cell;
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::iter::FromIterator;
+use std::ops::Index;
+
+const PAGE_SIZE: usize = 1024;
+
+type Page<C> = Box<[C]>;
+type Pages<C> = HashMap<usize, Page<C>, BuildHasherDefault<PageHasher>>;
+
+/// Page numbers are small and distinct, hashing them with the default hasher
+/// costs more than most instructions do
+#[derive(Default)]
+struct PageHasher(u64);
+
+impl Hasher for PageHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 << 8) | u64::from(byte);
+        }
+    }
+
+    fn write_usize(&mut self, number: usize) {
+        // spread the bits over the whole hash, the hash map uses the top ones
+        self.0 = (number as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+/// Memory of an Intcode computer
+///
+/// The loaded program is kept in a dense vector, everything written beyond it
+/// ends up in pages that are only allocated on the first write. Addresses that
+/// were never written read as 0, negative addresses are invalid.
+#[derive(Debug, Clone, Default)]
+pub struct Memory<C: Cell = i64> {
+    image: Vec<C>,
+    pages: Pages<C>,
+    /// What addresses that were never written to refer to
+    zero: C,
+}
+
+impl<C: Cell> Memory<C> {
+    /// Read the value at the given address
+    pub fn get(&self, address: i64) -> C {
+        self[address].clone()
+    }
+
+    /// Write a value to the given address
+    pub fn set(&mut self, address: i64, value: C) {
+        let address = to_index(address);
+        if address < self.image.len() {
+            self.image[address] = value;
+            return;
+        }
+
+        let page = self
+            .pages
+            .entry(address / PAGE_SIZE)
+            .or_insert_with(|| vec![C::default(); PAGE_SIZE].into());
+        page[address % PAGE_SIZE] = value;
+    }
+
+    /// One past the highest address that was loaded or holds a non-zero value
+    pub fn len(&self) -> usize {
+        let paged = self
+            .ranges()
+            .last()
+            .map_or(0, |&(start, cells)| start as usize + cells.len());
+        self.image.len().max(paged)
+    }
+
+    /// The loaded image and what was written beyond it, as the address of
+    /// their first cell and the cells, in order of address
+    ///
+    /// The image always comes first, even when it is empty. Zeros at either
+    /// end of a page are left out and pages holding only zeros are skipped,
+    /// so this only takes as long as memory is populated.
+    pub fn ranges(&self) -> Vec<(i64, &[C])> {
+        let mut pages: Vec<_> = self
+            .pages
+            .iter()
+            .filter_map(|(index, page)| {
+                let first =
+                    page.iter().position(|value| value != &self.zero)?;
+                let last =
+                    page.iter().rposition(|value| value != &self.zero)?;
+                let start = index * PAGE_SIZE + first;
+                Some((start as i64, &page[first..=last]))
+            })
+            .collect();
+        pages.sort_by_key(|&(start, _)| start);
+        let image = (0, &self.image[..]);
+        Some(image).into_iter().chain(pages).collect()
+    }
+
+    /// Amount of cells the program was loaded with
+    pub(super) fn loaded(&self) -> usize {
+        self.image.len()
+    }
+
+    /// Amount of memory cells that are allocated, which only grows a page at a
+    /// time
+    pub fn footprint(&self) -> usize {
+        self.image.len() + self.pages.len() * PAGE_SIZE
+    }
+}
+
+fn to_index(address: i64) -> usize {
+    assert!(address >= 0, "Accessing negative memory address: {}", address);
+    address as usize
+}
+
+impl<C: Cell> Index<i64> for Memory<C> {
+    type Output = C;
+
+    fn index(&self, address: i64) -> &C {
+        let address = to_index(address);
+        if address < self.image.len() {
+            return &self.image[address];
+        }
+
+        match self.pages.get(&(address / PAGE_SIZE)) {
+            Some(page) => &page[address % PAGE_SIZE],
+            None => &self.zero,
+        }
+    }
+}
+
+/// Addresses that were never written equal those holding a 0
+impl<C: Cell> PartialEq for Memory<C> {
+    fn eq(&self, other: &Memory<C>) -> bool {
+        let covers = |memory: &Memory<C>, other: &Memory<C>| {
+            memory.ranges().into_iter().all(|(start, cells)| {
+                (start..)
+                    .zip(cells)
+                    .all(|(address, value)| &other[address] == value)
+            })
+        };
+        covers(self, other) && covers(other, self)
+    }
+}
+
+impl<C: Cell> Eq for Memory<C> {}
+
+impl<C: Cell> From<Vec<C>> for Memory<C> {
+    fn from(image: Vec<C>) -> Memory<C> {
+        Memory { image, pages: Pages::default(), zero: C::default() }
+    }
+}
+
+impl<C: Cell> FromIterator<C> for Memory<C> {
+    fn from_iter<I: IntoIterator<Item = C>>(iter: I) -> Memory<C> {
+        Memory::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Memory = super::Memory<i64>;
+
+    #[test]
+    fn test_get_loaded() {
+        let memory = Memory::from(vec![1, 2, 3]);
+
+        assert_eq!(memory.get(0), 1);
+        assert_eq!(memory.get(2), 3);
+    }
+
+    #[test]
+    fn test_get_unknown_is_zero() {
+        let memory = Memory::from(vec![1, 2, 3]);
+
+        assert_eq!(memory.get(3), 0);
+        assert_eq!(memory.get(1_000_000), 0);
+    }
+
+    #[test]
+    fn test_set_loaded() {
+        let mut memory = Memory::from(vec![1, 2, 3]);
+        memory.set(1, 20);
+
+        assert_eq!(memory, Memory::from(vec![1, 20, 3]));
+    }
+
+    #[test]
+    fn test_set_high_address() {
+        let mut memory = Memory::from(vec![1, 2, 3]);
+        memory.set(5000, 7);
+
+        assert_eq!(memory.get(5000), 7);
+        assert_eq!(memory.get(4999), 0);
+        assert_eq!(memory.len(), 5001);
+        assert_eq!(memory.footprint(), 3 + PAGE_SIZE);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_get_negative_address() {
+        let memory = Memory::from(vec![1, 2, 3]);
+        memory.get(-1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_negative_address() {
+        let mut memory = Memory::default();
+        memory.set(-1, 1);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut memory = Memory::from(vec![1, 2, 3]);
+        memory.set(5000, 7);
+        memory.set(5002, 8);
+        memory.set(1 << 40, 9);
+        memory.set(3000, 0);
+
+        assert_eq!(
+            memory.ranges(),
+            vec![
+                (0, &[1, 2, 3][..]),
+                (5000, &[7, 0, 8][..]),
+                (1 << 40, &[9][..])
+            ]
+        );
+        assert_eq!(Memory::default().ranges(), vec![(0, &[][..])]);
+    }
+
+    #[test]
+    fn test_eq_is_sparse() {
+        let mut memory = Memory::from(vec![1, 2]);
+        memory.set(1 << 40, 3);
+        let mut other = Memory::from(vec![1, 2, 0]);
+        other.set(1 << 40, 3);
+
+        assert_eq!(memory, other);
+        other.set(1 << 41, 4);
+        assert_ne!(memory, other);
+        assert_ne!(other, memory);
+    }
+
+    #[test]
+    fn test_eq_ignores_trailing_zeroes() {
+        let mut memory = Memory::from(vec![1, 2]);
+        memory.set(2000, 0);
+
+        assert_eq!(memory, Memory::from(vec![1, 2, 0, 0]));
+        assert_ne!(memory, Memory::from(vec![1, 2, 0, 1]));
+    }
+}
@@ -0,0 +1,267 @@
+use intcode::{Input, IntcodeError, Output, Program, Runner, Sink, Source};
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+pub type NodeId = usize;
+
+/// A number of Intcode machines whose outputs are wired to each other's inputs
+///
+/// Every machine runs on its own thread. Links form an arbitrary directed
+/// graph: an output is delivered to every node the producing node is connected
+/// to, a node receives the inputs of all nodes connected to it in the order
+/// they were sent.
+#[derive(Debug, Default)]
+pub struct Network {
+    programs: Vec<Program>,
+    links: Vec<Vec<NodeId>>,
+    queues: Vec<VecDeque<Input>>,
+}
+
+impl Network {
+    pub fn new() -> Network {
+        Network::default()
+    }
+
+    /// Add a machine running the given program to the network
+    pub fn add_node(&mut self, program: Program) -> NodeId {
+        self.programs.push(program);
+        self.links.push(Vec::new());
+        self.queues.push(VecDeque::new());
+        self.programs.len() - 1
+    }
+
+    /// Deliver every output of `from` as an input to `to`
+    pub fn connect(&mut self, from: NodeId, to: NodeId) {
+        assert!(from < self.programs.len(), "Unknown node: {}", from);
+        assert!(to < self.programs.len(), "Unknown node: {}", to);
+        self.links[from].push(to);
+    }
+
+    /// Queue an input for a node before the network starts
+    pub fn send(&mut self, to: NodeId, input: Input) {
+        self.queues[to].push_back(input);
+    }
+
+    /// Run all machines until every one of them has either finished or is
+    /// waiting for an input that can never arrive
+    ///
+    /// The returned runners are ordered by node and hold all outputs their
+    /// machine produced. A node that was still waiting when the network went
+    /// idle has the `WaitingForInput` status, so it can be inspected or
+    /// resumed. When machines fault, the fault of the one with the lowest node
+    /// id is returned as an error, whichever faulted first.
+    pub fn run(self) -> Result<Vec<Runner>, IntcodeError> {
+        let Network { programs, links, queues } = self;
+        let nodes = programs.len();
+        let wiring = Wiring {
+            state: Mutex::new(State {
+                queues,
+                waiting: vec![false; nodes],
+                stopped: vec![false; nodes],
+                idle: false,
+            }),
+            signal: Condvar::new(),
+        };
+        let wiring = &wiring;
+        let links = &links;
+
+        let results = thread::scope(|scope| {
+            let handles: Vec<_> = programs
+                .into_iter()
+                .enumerate()
+                .map(|(node, program)| {
+                    scope.spawn(move || {
+                        let mut outputs = Vec::new();
+                        let result = Runner::new(program).run(
+                            &mut Inbox { node, wiring },
+                            &mut Outbox {
+                                targets: &links[node],
+                                wiring,
+                                outputs: &mut outputs,
+                            },
+                        );
+                        wiring.stop(node);
+                        result
+                            .and_then(Runner::or_fault)
+                            .map(|runner| Runner { outputs, ..runner })
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Network node panicked"))
+                .collect::<Vec<_>>()
+        });
+
+        results.into_iter().collect()
+    }
+}
+
+struct State {
+    queues: Vec<VecDeque<Input>>,
+    waiting: Vec<bool>,
+    stopped: Vec<bool>,
+    idle: bool,
+}
+
+impl State {
+    /// The network is idle when no node can make progress anymore
+    fn is_idle(&self) -> bool {
+        let all_blocked = (0..self.queues.len()).all(|node| {
+            self.stopped[node]
+                || (self.waiting[node] && self.queues[node].is_empty())
+        });
+        all_blocked && self.waiting.iter().any(|&waiting| waiting)
+    }
+}
+
+struct Wiring {
+    state: Mutex<State>,
+    signal: Condvar,
+}
+
+impl Wiring {
+    fn stop(&self, node: NodeId) {
+        let mut state = self.state.lock().unwrap();
+        state.stopped[node] = true;
+        if state.is_idle() {
+            state.idle = true;
+            self.signal.notify_all();
+        }
+    }
+}
+
+struct Inbox<'a> {
+    node: NodeId,
+    wiring: &'a Wiring,
+}
+
+impl<'a> Source for Inbox<'a> {
+    fn read(&mut self) -> Option<Input> {
+        let mut state = self.wiring.state.lock().unwrap();
+        loop {
+            if let Some(input) = state.queues[self.node].pop_front() {
+                state.waiting[self.node] = false;
+                return Some(input);
+            }
+            if state.idle {
+                return None;
+            }
+
+            state.waiting[self.node] = true;
+            if state.is_idle() {
+                state.idle = true;
+                self.wiring.signal.notify_all();
+                return None;
+            }
+            state = self.wiring.signal.wait(state).unwrap();
+        }
+    }
+}
+
+struct Outbox<'a> {
+    targets: &'a [NodeId],
+    wiring: &'a Wiring,
+    outputs: &'a mut Vec<Output>,
+}
+
+impl<'a> Sink for Outbox<'a> {
+    fn write(&mut self, output: Output) {
+        self.outputs.push(output);
+        if self.targets.is_empty() {
+            return;
+        }
+
+        let mut state = self.wiring.state.lock().unwrap();
+        for &target in self.targets {
+            state.queues[target].push_back(output);
+        }
+        self.wiring.signal.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::ExitStatus;
+
+    #[test]
+    fn test_chain() {
+        // add one to every input
+        let increment = program![3, 9, 1001, 9, 1, 9, 4, 9, 99, 0];
+        let mut network = Network::new();
+        let first = network.add_node(increment.clone());
+        let second = network.add_node(increment);
+        network.connect(first, second);
+        network.send(first, 40);
+
+        let runners = network.run().unwrap();
+
+        assert_eq!(runners[second].state.status, ExitStatus::Halted);
+        assert_eq!(runners[first].outputs, vec![41]);
+        assert_eq!(runners[second].outputs, vec![42]);
+    }
+
+    #[test]
+    fn test_fan_out() {
+        let echo = program![3, 0, 4, 0, 99];
+        let mut network = Network::new();
+        let source = network.add_node(echo.clone());
+        let left = network.add_node(echo.clone());
+        let right = network.add_node(echo);
+        network.connect(source, left);
+        network.connect(source, right);
+        network.send(source, 7);
+
+        let runners = network.run().unwrap();
+
+        assert_eq!(runners[left].outputs, vec![7]);
+        assert_eq!(runners[right].outputs, vec![7]);
+    }
+
+    #[test]
+    fn test_idle_detection() {
+        // both nodes wait for a second input the other never sends
+        let mut network = Network::new();
+        let first = network.add_node(program![3, 10, 4, 10, 3, 10, 99]);
+        let second = network.add_node(program![3, 10, 3, 10, 4, 10, 99]);
+        network.connect(first, second);
+        network.connect(second, first);
+        network.send(first, 1);
+
+        let runners = network.run().unwrap();
+
+        assert_eq!(runners[first].state.status, ExitStatus::WaitingForInput);
+        assert_eq!(runners[first].state.ip, 4);
+        assert_eq!(runners[second].state.status, ExitStatus::WaitingForInput);
+        assert_eq!(runners[second].state.ip, 2);
+        assert_eq!(runners[first].outputs, vec![1]);
+        assert_eq!(runners[second].outputs, vec![]);
+    }
+
+    #[test]
+    fn test_fault_is_reported() {
+        let mut network = Network::new();
+        network.add_node(program![3, 0, 4, 0, 99]);
+        network.add_node(program![42]);
+
+        assert_eq!(
+            network.run(),
+            Err(IntcodeError::UnknownOpcode {
+                opcode: 42,
+                position: 0,
+                base: 0
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown node: 1")]
+    fn test_connect_from_unknown_node() {
+        let mut network = Network::new();
+        let node = network.add_node(program![99]);
+        network.connect(1, node);
+    }
+}
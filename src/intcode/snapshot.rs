@@ -0,0 +1,433 @@
+use intcode::{
+    dump, parse_as, ExitStatus, Limit, MachineState, Outputs, ParseError,
+    Program, Runner,
+};
+use std::error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+
+const MAGIC: &[u8; 3] = b"ICS";
+/// Follows the magic, bumped whenever the binary layout changes
+const VERSION: u8 = 2;
+const HEADER: &str = "intcode snapshot";
+
+/// How a snapshot is laid out on disk
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    /// Variable length integers, small values take up a single byte
+    Binary,
+    /// Line based, memory in the notation of `dump` and outputs in that of
+    /// puzzle inputs
+    Text,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(f, "{}", error),
+            SnapshotError::Invalid(message) => {
+                write!(f, "invalid snapshot: {}", message)
+            }
+        }
+    }
+}
+
+impl error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> SnapshotError {
+        SnapshotError::Io(error)
+    }
+}
+
+/// Write the full state of a runner: its memory, machine state and the
+/// outputs it still holds
+///
+/// A faulted runner cannot be resumed, so it cannot be saved either.
+pub fn save_snapshot<W: Write>(
+    runner: &Runner,
+    mut writer: W,
+    format: Format,
+) -> io::Result<()> {
+    let status = match runner.state.status {
+        ExitStatus::WaitingForInput => "waiting",
+        ExitStatus::OutputReady => "output",
+        ExitStatus::Halted => "halted",
+        ExitStatus::LimitReached(Limit::Instructions) => "instruction-limit",
+        ExitStatus::LimitReached(Limit::Memory) => "memory-limit",
+        ExitStatus::LimitReached(Limit::Deadline) => "deadline",
+        ExitStatus::Faulted(ref error) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot save a faulted runner: {}", error),
+            ))
+        }
+    };
+    let state = &runner.state;
+
+    match format {
+        Format::Binary => {
+            writer.write_all(MAGIC)?;
+            writer.write_all(&[VERSION])?;
+            let tag = STATUSES.iter().position(|&s| s == status).unwrap();
+            writer.write_all(&[tag as u8])?;
+            write_number(&mut writer, state.ip)?;
+            write_number(&mut writer, state.relative_base)?;
+            write_number(&mut writer, state.cycles as i64)?;
+            write_numbers(&mut writer, &runner.outputs)?;
+            // the image, then every other range by its first address
+            let ranges = runner.program.ranges();
+            write_numbers(&mut writer, ranges[0].1)?;
+            write_number(&mut writer, ranges.len() as i64 - 1)?;
+            for &(start, cells) in &ranges[1..] {
+                write_number(&mut writer, start)?;
+                write_numbers(&mut writer, cells)?;
+            }
+        }
+        Format::Text => {
+            writeln!(writer, "{}", HEADER)?;
+            writeln!(writer, "status {}", status)?;
+            writeln!(writer, "ip {}", state.ip)?;
+            writeln!(writer, "rb {}", state.relative_base)?;
+            writeln!(writer, "cycles {}", state.cycles)?;
+            let outputs: Vec<_> = runner
+                .outputs
+                .iter()
+                .map(|output| output.to_string())
+                .collect();
+            writeln!(writer, "outputs {}", outputs.join(","))?;
+            writeln!(writer, "memory {}", dump(&runner.program))?;
+        }
+    }
+    writer.flush()
+}
+
+/// Read a runner back from a snapshot in either format
+pub fn load_snapshot<R: Read>(reader: R) -> Result<Runner, SnapshotError> {
+    let mut reader = BufReader::new(reader);
+    if reader.fill_buf()?.starts_with(MAGIC) {
+        reader.consume(MAGIC.len());
+        load_binary(&mut reader)
+    } else {
+        load_text(reader)
+    }
+}
+
+fn load_binary<R: Read>(reader: &mut R) -> Result<Runner, SnapshotError> {
+    let mut version = [0];
+    reader.read_exact(&mut version)?;
+    if version[0] != VERSION {
+        return Err(invalid(format!("unsupported version {}", version[0])));
+    }
+    let mut tag = [0];
+    reader.read_exact(&mut tag)?;
+    let status = STATUSES
+        .get(tag[0] as usize)
+        .ok_or_else(|| invalid(format!("unknown status {}", tag[0])))?;
+    let state = MachineState {
+        status: parse_status(status)?,
+        ip: read_number(reader)?,
+        relative_base: read_number(reader)?,
+        cycles: read_number(reader)? as u64,
+    };
+    let outputs = read_numbers(reader)?;
+    let mut program = Program::from(read_numbers(reader)?);
+    for _ in 0..read_count(reader)? {
+        let start = read_number(reader)?;
+        place(&mut program, start, read_numbers(reader)?)?;
+    }
+    Ok(Runner::resumed(program, state, outputs))
+}
+
+fn load_text<R: BufRead>(reader: R) -> Result<Runner, SnapshotError> {
+    let mut lines = reader.lines();
+    let mut line = |key: &str| -> Result<String, SnapshotError> {
+        let line = lines
+            .next()
+            .ok_or_else(|| invalid(format!("missing {}", key)))??;
+        if line == key {
+            return Ok(String::new());
+        }
+        match line.strip_prefix(key) {
+            Some(rest) if rest.starts_with(' ') => Ok(rest.trim().to_string()),
+            _ => Err(invalid(format!("expected {}, got {}", key, line))),
+        }
+    };
+
+    line(HEADER)?;
+    let status = parse_status(&line("status")?)?;
+    let state = MachineState {
+        status,
+        ip: parse_number("ip", &line("ip")?)?,
+        relative_base: parse_number("rb", &line("rb")?)?,
+        cycles: parse_number("cycles", &line("cycles")?)?,
+    };
+    let outputs = parse_numbers("output", &line("outputs")?)?;
+    let program = parse_memory(&line("memory")?)?;
+    Ok(Runner::resumed(program, state, outputs))
+}
+
+fn parse_number<T: FromStr>(key: &str, text: &str) -> Result<T, SnapshotError> {
+    text.trim().parse().map_err(|_| invalid(format!("bad {} {}", key, text)))
+}
+
+/// Comma separated numbers, every one of which has to be valid
+fn parse_numbers(key: &str, text: &str) -> Result<Vec<i64>, SnapshotError> {
+    if text.is_empty() {
+        return Ok(Vec::new());
+    }
+    text.split(',').map(|number| parse_number(key, number)).collect()
+}
+
+/// Memory as `dump` writes it: the image, then `;<address>:<values>` for
+/// every other range
+fn parse_memory(text: &str) -> Result<Program, SnapshotError> {
+    parse_as(text).map_err(|error| {
+        invalid(match error {
+            ParseError::Value(value) => format!("bad memory value {}", value),
+            ParseError::Range(range) => format!("bad memory range {}", range),
+            ParseError::Address(address) => {
+                format!("bad memory address {}", address)
+            }
+        })
+    })
+}
+
+/// Write the values to memory from `start` on
+fn place(
+    program: &mut Program,
+    start: i64,
+    values: Vec<i64>,
+) -> Result<(), SnapshotError> {
+    let fits = start >= 0 && start.checked_add(values.len() as i64).is_some();
+    if !fits {
+        return Err(invalid(format!("bad memory address {}", start)));
+    }
+    for (address, value) in (start..).zip(values) {
+        program.set(address, value);
+    }
+    Ok(())
+}
+
+/// Statuses a snapshot can hold, binary snapshots store the index
+const STATUSES: [&str; 6] = [
+    "waiting",
+    "output",
+    "halted",
+    "instruction-limit",
+    "memory-limit",
+    "deadline",
+];
+
+fn parse_status(status: &str) -> Result<ExitStatus, SnapshotError> {
+    match status {
+        "waiting" => Ok(ExitStatus::WaitingForInput),
+        "output" => Ok(ExitStatus::OutputReady),
+        "halted" => Ok(ExitStatus::Halted),
+        "instruction-limit" => {
+            Ok(ExitStatus::LimitReached(Limit::Instructions))
+        }
+        "memory-limit" => Ok(ExitStatus::LimitReached(Limit::Memory)),
+        "deadline" => Ok(ExitStatus::LimitReached(Limit::Deadline)),
+        _ => Err(invalid(format!("bad status {}", status))),
+    }
+}
+
+fn invalid(message: String) -> SnapshotError {
+    SnapshotError::Invalid(message)
+}
+
+/// Zigzag encode a number, then write it 7 bits at a time
+fn write_number<W: Write>(writer: &mut W, number: i64) -> io::Result<()> {
+    let mut value = ((number << 1) ^ (number >> 63)) as u64;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_number<R: Read>(reader: &mut R) -> Result<i64, SnapshotError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+        }
+    }
+    Err(invalid("number is too long".to_string()))
+}
+
+/// A count followed by that many numbers
+fn write_numbers<W: Write>(writer: &mut W, numbers: &[i64]) -> io::Result<()> {
+    write_number(writer, numbers.len() as i64)?;
+    numbers.iter().try_for_each(|&number| write_number(writer, number))
+}
+
+fn read_count<R: Read>(reader: &mut R) -> Result<i64, SnapshotError> {
+    let count = read_number(reader)?;
+    if count < 0 {
+        return Err(invalid(format!("negative count {}", count)));
+    }
+    Ok(count)
+}
+
+fn read_numbers<R: Read>(reader: &mut R) -> Result<Outputs, SnapshotError> {
+    let count = read_count(reader)?;
+    (0..count).map(|_| read_number(reader)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::{start, Step};
+
+    fn round_trip(runner: &Runner, format: Format) -> Runner {
+        let mut bytes = Vec::new();
+        save_snapshot(runner, &mut bytes, format).unwrap();
+        load_snapshot(&bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // output the input, move the relative base and wait for another input
+        let program = program![3, 100, 4, 100, 109, 5, 3, 2000, 99];
+        let runner = start(program).unwrap().step(-7).unwrap();
+        assert_eq!(runner.state.status, ExitStatus::WaitingForInput);
+        assert_eq!((runner.state.ip, runner.state.relative_base), (6, 5));
+        assert_eq!(runner.outputs, vec![-7]);
+
+        assert_eq!(round_trip(&runner, Format::Binary), runner);
+        assert_eq!(round_trip(&runner, Format::Text), runner);
+    }
+
+    #[test]
+    fn test_resume_from_snapshot() {
+        // add two inputs
+        let program = program![3, 11, 3, 12, 1, 11, 12, 13, 4, 13, 99];
+        let runner = start(program).unwrap().step(40).unwrap();
+
+        let resumed = round_trip(&runner, Format::Binary).step(2).unwrap();
+
+        assert_eq!(resumed.state.status, ExitStatus::Halted);
+        assert_eq!(resumed.outputs, vec![42]);
+    }
+
+    #[test]
+    fn test_binary_is_compact() {
+        let runner = Runner::new(program![1, 2, 3, 4, 99]);
+        let mut bytes = Vec::new();
+        save_snapshot(&runner, &mut bytes, Format::Binary).unwrap();
+
+        // magic, version, status, ip, rb, cycles, no outputs, image length,
+        // image, no other ranges
+        assert_eq!(bytes.len(), 3 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 6 + 1);
+    }
+
+    #[test]
+    fn test_text_format() {
+        let runner = Runner::resumed(
+            program![104, 1, 99],
+            MachineState {
+                ip: 2,
+                relative_base: 0,
+                cycles: 2,
+                status: ExitStatus::Halted,
+            },
+            vec![1, 2],
+        );
+        let mut bytes = Vec::new();
+        save_snapshot(&runner, &mut bytes, Format::Text).unwrap();
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "intcode snapshot\nstatus halted\nip 2\nrb 0\ncycles 2\n\
+             outputs 1,2\nmemory 104,1,99\n"
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        for &number in
+            [0, 1, -1, 63, -64, 64, 1 << 40, i64::MIN, i64::MAX].iter()
+        {
+            let mut bytes = Vec::new();
+            write_number(&mut bytes, number).unwrap();
+            assert_eq!(read_number(&mut &bytes[..]).unwrap(), number);
+        }
+    }
+
+    #[test]
+    fn test_sparse_memory() {
+        let mut program = program![109, 5, 99];
+        program.set(1 << 50, 7);
+        let runner = Runner::new(program);
+
+        let mut bytes = Vec::new();
+        save_snapshot(&runner, &mut bytes, Format::Binary).unwrap();
+        assert!(bytes.len() < 32);
+        assert_eq!(round_trip(&runner, Format::Binary), runner);
+        assert_eq!(round_trip(&runner, Format::Text), runner);
+    }
+
+    #[test]
+    fn test_invalid_memory() {
+        let snapshot = |memory: &str| {
+            let text = format!(
+                "intcode snapshot\nstatus waiting\nip 0\nrb 0\ncycles 0\n\
+                 outputs\nmemory {}\n",
+                memory
+            );
+            load_snapshot(text.as_bytes()).map_err(|error| error.to_string())
+        };
+
+        assert!(snapshot("3,0,99;5000:1,2").is_ok());
+        assert_eq!(
+            snapshot("3,x,99").unwrap_err(),
+            "invalid snapshot: bad memory value x"
+        );
+        assert_eq!(
+            snapshot("3,0,99;-5:1").unwrap_err(),
+            "invalid snapshot: bad memory address -5"
+        );
+        assert_eq!(
+            snapshot("3,0,99;5000").unwrap_err(),
+            "invalid snapshot: bad memory range 5000"
+        );
+    }
+
+    #[test]
+    fn test_unknown_version() {
+        let runner = Runner::new(program![99]);
+        let mut bytes = Vec::new();
+        save_snapshot(&runner, &mut bytes, Format::Binary).unwrap();
+        bytes[3] = 1;
+
+        assert_eq!(
+            load_snapshot(&bytes[..]).unwrap_err().to_string(),
+            "invalid snapshot: unsupported version 1"
+        );
+    }
+
+    #[test]
+    fn test_invalid_snapshot() {
+        let result = load_snapshot(&b"intcode snapshot\nstatus running\n"[..]);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid snapshot: bad status running"
+        );
+    }
+}
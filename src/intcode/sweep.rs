@@ -0,0 +1,330 @@
+//! Brute forcing the values of some cells of a program
+//!
+//! Every combination of values is a separate run of the program, the runs are
+//! spread over threads in chunks. Puzzles like day 2 ask for the noun and verb
+//! that make a program compute a given value, a sweep over both finds them.
+
+use intcode::{io, Inputs, Limits, Program, Runner};
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Amount of combinations a thread takes at once
+const CHUNK: u64 = 16;
+
+/// Values to try for the cell at an address
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Patch {
+    pub address: i64,
+    pub values: RangeInclusive<i64>,
+}
+
+impl Patch {
+    fn len(&self) -> i128 {
+        let (start, end) = (*self.values.start(), *self.values.end());
+        (i128::from(end) - i128::from(start) + 1).max(0)
+    }
+}
+
+/// A patched program the objective accepted
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Match {
+    /// The value of every patch, in order
+    pub values: Vec<i64>,
+    /// Where the program ended up, holding every output it produced
+    pub runner: Runner,
+}
+
+/// Why the patches cannot be swept
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SweepError {
+    /// The patches have more combinations than fit a `u64`
+    TooManyCombinations,
+    /// A patch of a negative address, which memory does not have
+    NegativeAddress(i64),
+}
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SweepError::TooManyCombinations => {
+                write!(f, "too many combinations to sweep")
+            }
+            SweepError::NegativeAddress(address) => {
+                write!(f, "cannot patch negative address {}", address)
+            }
+        }
+    }
+}
+
+impl error::Error for SweepError {}
+
+/// Runs a program with every combination of values for some of its cells,
+/// spread over a number of threads
+///
+/// Combinations are ordered with the last patch changing fastest. Runs that
+/// fault never match.
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    program: Program,
+    patches: Vec<Patch>,
+    inputs: Inputs,
+    limits: Limits,
+    threads: usize,
+}
+
+impl Sweep {
+    /// A sweep without inputs or limits, on as many threads as there are
+    /// cores
+    pub fn new(program: Program, patches: Vec<Patch>) -> Sweep {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        Sweep {
+            program,
+            patches,
+            inputs: Inputs::new(),
+            limits: Limits::none(),
+            threads,
+        }
+    }
+
+    /// Inputs every run starts with
+    pub fn inputs(self, inputs: Inputs) -> Sweep {
+        Sweep { inputs, ..self }
+    }
+
+    /// Limits of every single run
+    pub fn limits(self, limits: Limits) -> Sweep {
+        Sweep { limits, ..self }
+    }
+
+    pub fn threads(self, threads: usize) -> Sweep {
+        Sweep { threads: threads.max(1), ..self }
+    }
+
+    /// Amount of combinations of the patches, `None` when they do not fit a
+    /// `u64`
+    pub fn combinations(&self) -> Option<u64> {
+        self.patches
+            .iter()
+            .try_fold(1i128, |product, patch| product.checked_mul(patch.len()))
+            .and_then(|combinations| u64::try_from(combinations).ok())
+    }
+
+    /// Every match, in order of the combinations
+    pub fn all<F>(&self, objective: F) -> Result<Vec<Match>, SweepError>
+    where
+        F: Fn(&Runner) -> bool + Sync,
+    {
+        self.search(&objective, false)
+    }
+
+    /// The first match in order of the combinations, the threads stop once it
+    /// is certain no earlier one exists
+    pub fn first<F>(&self, objective: F) -> Result<Option<Match>, SweepError>
+    where
+        F: Fn(&Runner) -> bool + Sync,
+    {
+        let matches = self.search(&objective, true)?;
+        Ok(matches.into_iter().next())
+    }
+
+    fn search<F>(
+        &self,
+        objective: &F,
+        first: bool,
+    ) -> Result<Vec<Match>, SweepError>
+    where
+        F: Fn(&Runner) -> bool + Sync,
+    {
+        let patch = self.patches.iter().find(|patch| patch.address < 0);
+        if let Some(patch) = patch {
+            return Err(SweepError::NegativeAddress(patch.address));
+        }
+        let combinations =
+            self.combinations().ok_or(SweepError::TooManyCombinations)?;
+        let next = AtomicU64::new(0);
+        // the earliest match so far, later chunks are skipped for `first`
+        let earliest = AtomicU64::new(u64::MAX);
+        let matches = Mutex::new(Vec::new());
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads {
+                scope.spawn(|| loop {
+                    let start = next.fetch_add(CHUNK, Ordering::Relaxed);
+                    if start >= combinations
+                        || (first && start > earliest.load(Ordering::Relaxed))
+                    {
+                        break;
+                    }
+                    let end = start.saturating_add(CHUNK).min(combinations);
+                    for index in start..end {
+                        let values = self.values(index);
+                        let runner = match self.run(&values) {
+                            Some(runner) if objective(&runner) => runner,
+                            _ => continue,
+                        };
+                        let found = Match { values, runner };
+                        matches.lock().unwrap().push((index, found));
+                        if first {
+                            earliest.fetch_min(index, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut matches = matches.into_inner().unwrap();
+        matches.sort_by_key(|&(index, _)| index);
+        Ok(matches.into_iter().map(|(_, found)| found).collect())
+    }
+
+    /// The values of the combination at `index`
+    fn values(&self, mut index: u64) -> Vec<i64> {
+        let mut values = vec![0; self.patches.len()];
+        for (value, patch) in values.iter_mut().zip(&self.patches).rev() {
+            let len = patch.len() as u64;
+            *value = (i128::from(*patch.values.start())
+                + i128::from(index % len)) as i64;
+            index /= len;
+        }
+        values
+    }
+
+    fn run(&self, values: &[i64]) -> Option<Runner> {
+        let mut program = self.program.clone();
+        for (patch, &value) in self.patches.iter().zip(values) {
+            program.set(patch.address, value);
+        }
+        let mut outputs = Vec::new();
+        let runner = Runner::new(program)
+            .run_limited(
+                &mut io::iter(self.inputs.clone()),
+                &mut outputs,
+                &self.limits,
+            )
+            .and_then(Runner::or_fault)
+            .ok()?;
+        Some(Runner { outputs, ..runner })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::{ExitStatus, Limit};
+
+    // output [11] * [12] + [13]
+    fn program() -> Program {
+        program![2, 11, 12, 14, 1, 14, 13, 14, 4, 14, 99, 0, 0, 0, 0]
+    }
+
+    fn patches() -> Vec<Patch> {
+        vec![
+            Patch { address: 11, values: 1..=6 },
+            Patch { address: 12, values: 1..=6 },
+            Patch { address: 13, values: -1..=1 },
+        ]
+    }
+
+    fn outputs(expected: i64) -> impl Fn(&Runner) -> bool {
+        move |runner| runner.outputs == vec![expected]
+    }
+
+    #[test]
+    fn test_all() {
+        let sweep = Sweep::new(program(), patches());
+
+        let values: Vec<_> = sweep
+            .all(outputs(7))
+            .unwrap()
+            .into_iter()
+            .map(|m| m.values)
+            .collect();
+
+        assert_eq!(sweep.combinations(), Some(108));
+        assert_eq!(
+            values,
+            vec![
+                vec![1, 6, 1],
+                vec![2, 3, 1],
+                vec![2, 4, -1],
+                vec![3, 2, 1],
+                vec![4, 2, -1],
+                vec![6, 1, 1],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first() {
+        for threads in 1..=4 {
+            let sweep = Sweep::new(program(), patches()).threads(threads);
+
+            let found = sweep.first(outputs(12)).unwrap().unwrap();
+
+            assert_eq!(found.values, vec![2, 6, 0]);
+            assert_eq!(found.runner.program.get(14), 12);
+            assert_eq!(sweep.first(outputs(100)), Ok(None));
+        }
+    }
+
+    #[test]
+    fn test_objective_on_state() {
+        // read an input into [0] unless [7] is 0
+        let program = program![1006, 7, 5, 3, 0, 99, 0, 0];
+        let patches = vec![Patch { address: 7, values: 0..=3 }];
+
+        let sweep = Sweep::new(program, patches).inputs(vec![7]);
+        let halted = sweep
+            .all(|runner| {
+                runner.state.status == ExitStatus::Halted
+                    && runner.program.get(0) == 7
+            })
+            .unwrap();
+
+        let values: Vec<_> = halted.into_iter().map(|m| m.values).collect();
+        assert_eq!(values, vec![vec![1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn test_faults_and_limits() {
+        // jump to the patched address
+        let program = program![1105, 1, 0, 99];
+        let patches = vec![Patch { address: 2, values: 0..=3 }];
+        let limits = Limits { max_instructions: Some(100), ..Limits::none() };
+
+        let sweep = Sweep::new(program, patches).limits(limits);
+        let matches = sweep.all(|_| true).unwrap();
+
+        // jumping to 1 or 2 faults, to 0 loops until the limit, to 3 halts
+        let values: Vec<_> = matches.iter().map(|m| &m.values).collect();
+        assert_eq!(values, vec![&vec![0], &vec![3]]);
+        assert_eq!(
+            matches[0].runner.state.status,
+            ExitStatus::LimitReached(Limit::Instructions)
+        );
+    }
+
+    #[test]
+    fn test_too_many_combinations() {
+        let patch = |address| Patch { address, values: i64::MIN..=i64::MAX };
+        let sweep = Sweep::new(program(), vec![patch(11), patch(12)]);
+
+        assert_eq!(sweep.combinations(), None);
+        assert_eq!(sweep.all(|_| true), Err(SweepError::TooManyCombinations));
+        assert_eq!(sweep.first(|_| true), Err(SweepError::TooManyCombinations));
+    }
+
+    #[test]
+    fn test_negative_address() {
+        let patches = vec![Patch { address: -1, values: 0..=3 }];
+        let sweep = Sweep::new(program(), patches);
+
+        assert_eq!(sweep.all(|_| true), Err(SweepError::NegativeAddress(-1)));
+    }
+}
@@ -0,0 +1,511 @@
+//! Solving for the values of cells instead of trying them all
+//!
+//! The program runs with symbols in some of its cells, the values it computes
+//! from them are kept as polynomials. Branching on such a value or writing to
+//! it as an address needs a concrete run, `solve` falls back to searching all
+//! values then.
+
+use intcode::isa::STANDARD;
+use intcode::sweep::{Patch, Sweep};
+use intcode::{
+    extract_modes, offset, ExitStatus, Fault, IntcodeError, Limits, Mode,
+    Program, Runner,
+};
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Amount of instructions after which a program is not expected to halt
+const MAX_INSTRUCTIONS: u64 = 1_000_000;
+
+/// A polynomial in the initial values of symbolic cells
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Expression {
+    /// Coefficients by the symbols they are multiplied with, a symbol is the
+    /// address of its cell and the constant has none
+    terms: BTreeMap<Vec<i64>, i64>,
+}
+
+impl Expression {
+    pub fn constant(value: i64) -> Expression {
+        Expression::term(vec![], value)
+    }
+
+    /// The initial value of the cell at `address`
+    pub fn symbol(address: i64) -> Expression {
+        Expression::term(vec![address], 1)
+    }
+
+    fn term(symbols: Vec<i64>, coefficient: i64) -> Expression {
+        let mut terms = BTreeMap::new();
+        if coefficient != 0 {
+            terms.insert(symbols, coefficient);
+        }
+        Expression { terms }
+    }
+
+    fn as_constant(&self) -> Option<i64> {
+        match self.terms.iter().next() {
+            None => Some(0),
+            Some((symbols, &value)) if symbols.is_empty() => {
+                Some(value).filter(|_| self.terms.len() == 1)
+            }
+            Some(_) => None,
+        }
+    }
+
+    /// `None` when a coefficient overflows
+    fn add(&self, other: &Expression) -> Option<Expression> {
+        let mut terms = self.terms.clone();
+        for (symbols, &coefficient) in &other.terms {
+            let sum =
+                terms.get(symbols).unwrap_or(&0).checked_add(coefficient)?;
+            match sum {
+                0 => terms.remove(symbols),
+                _ => terms.insert(symbols.clone(), sum),
+            };
+        }
+        Some(Expression { terms })
+    }
+
+    /// `None` when a coefficient overflows
+    fn multiply(&self, other: &Expression) -> Option<Expression> {
+        let mut product = Expression::constant(0);
+        for (left, &a) in &self.terms {
+            for (right, &b) in &other.terms {
+                let mut symbols =
+                    left.iter().chain(right).cloned().collect_vec();
+                symbols.sort();
+                product = product
+                    .add(&Expression::term(symbols, a.checked_mul(b)?))?;
+            }
+        }
+        Some(product)
+    }
+
+    /// The constant and the coefficient of every symbol, `None` when the
+    /// expression is not linear in them
+    pub fn linear(&self, symbols: &[i64]) -> Option<(i64, Vec<i64>)> {
+        let mut constant = 0;
+        let mut coefficients = vec![0; symbols.len()];
+        for (term, &coefficient) in &self.terms {
+            match term.as_slice() {
+                [] => constant = coefficient,
+                [symbol] => {
+                    let index = symbols.iter().position(|s| s == symbol)?;
+                    coefficients[index] = coefficient;
+                }
+                _ => return None,
+            }
+        }
+        Some((constant, coefficients))
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        // highest degree first, the constant last
+        let mut terms = self.terms.iter().collect_vec();
+        terms.sort_by_key(|(symbols, _)| Reverse(symbols.len()));
+        for (index, (symbols, coefficient)) in terms.into_iter().enumerate() {
+            let factors = symbols.iter().map(|symbol| format!("[{}]", symbol));
+            let mut factors = factors.collect_vec();
+            if *coefficient != 1 || factors.is_empty() {
+                factors.insert(0, coefficient.abs().to_string());
+            }
+            let sign = match (index, *coefficient < 0) {
+                (0, true) => "-",
+                (0, false) => "",
+                (_, true) => " - ",
+                (_, false) => " + ",
+            };
+            write!(f, "{}{}", sign, factors.join("*"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reasons a program cannot be run symbolically
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SymbolicError {
+    /// An instruction that reads input, writes output or is not known
+    Unsupported {
+        opcode: i64,
+        position: i64,
+    },
+    /// The instruction branches on, jumps to or writes to a value that
+    /// depends on the symbols
+    Undecided {
+        position: i64,
+    },
+    /// The value at the address was read from an address that depends on the
+    /// symbols
+    Unknown {
+        address: i64,
+    },
+    Fault(IntcodeError),
+    /// The program did not halt
+    Endless,
+}
+
+impl fmt::Display for SymbolicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolicError::Unsupported { opcode, position } => write!(
+                f,
+                "opcode {} at position {} cannot run symbolically",
+                opcode, position
+            ),
+            SymbolicError::Undecided { position } => write!(
+                f,
+                "the instruction at position {} depends on the symbols",
+                position
+            ),
+            SymbolicError::Unknown { address } => {
+                write!(f, "the value at address {} is unknown", address)
+            }
+            SymbolicError::Fault(error) => write!(f, "{}", error),
+            SymbolicError::Endless => {
+                write!(f, "no halt after {} instructions", MAX_INSTRUCTIONS)
+            }
+        }
+    }
+}
+
+impl error::Error for SymbolicError {}
+
+/// Memory of a symbolic run, `None` for values read from an address that
+/// depends on the symbols
+struct Cells(HashMap<i64, Option<Expression>>);
+
+impl Cells {
+    fn get(&self, address: i64) -> Option<Expression> {
+        match self.0.get(&address) {
+            Some(value) => value.clone(),
+            None => Some(Expression::constant(0)),
+        }
+    }
+
+    fn set(&mut self, address: i64, value: Option<Expression>) {
+        self.0.insert(address, value);
+    }
+}
+
+/// Run the program with the cells at `symbols` left open, returning what is
+/// at `address` once it halts
+pub fn expression(
+    program: &Program,
+    symbols: &[i64],
+    address: i64,
+) -> Result<Expression, SymbolicError> {
+    let mut cells = Cells(
+        program
+            .ranges()
+            .into_iter()
+            .flat_map(|(start, values)| (start..).zip(values))
+            .map(|(address, &value)| {
+                (address, Some(Expression::constant(value)))
+            })
+            .collect(),
+    );
+    for &symbol in symbols {
+        cells.set(symbol, Some(Expression::symbol(symbol)));
+    }
+
+    let (mut position, mut base) = (0, 0);
+    for _ in 0..MAX_INSTRUCTIONS {
+        let undecided = SymbolicError::Undecided { position };
+        let fault =
+            move |fault: Fault| SymbolicError::Fault(fault.at(position, base));
+        let concrete = |value: Option<Expression>| {
+            value.and_then(|value| value.as_constant()).ok_or(undecided.clone())
+        };
+
+        let instruction = concrete(cells.get(position))?;
+        let (modes, opcode) = extract_modes(instruction).map_err(fault)?;
+        let operation = match (opcode, STANDARD.get(opcode)) {
+            (3, _) | (4, _) | (_, None) => {
+                return Err(SymbolicError::Unsupported { opcode, position })
+            }
+            (_, Some(operation)) => operation,
+        };
+
+        let mut params = vec![];
+        let mut write = 0;
+        for (index, mode) in modes.iter().enumerate().take(operation.arity) {
+            let number =
+                cells.get(offset(position, 1 + index as i64).map_err(fault)?);
+            let address = match mode {
+                Mode::Immediate if operation.write == Some(index) => {
+                    return Err(fault(Fault::ImmediateWrite))
+                }
+                Mode::Immediate => {
+                    params.push(number);
+                    continue;
+                }
+                Mode::Position => number.and_then(|n| n.as_constant()),
+                Mode::Relative => match number.and_then(|n| n.as_constant()) {
+                    Some(n) => Some(offset(base, n).map_err(fault)?),
+                    None => None,
+                },
+            };
+            match (address, operation.write == Some(index)) {
+                (Some(address), _) if address < 0 => {
+                    return Err(fault(Fault::NegativeAddress(address)))
+                }
+                (Some(address), true) => write = address,
+                (Some(address), false) => params.push(cells.get(address)),
+                (None, true) => return Err(undecided),
+                (None, false) => params.push(None),
+            }
+        }
+
+        let both = |params: &[Option<Expression>]| match params {
+            [Some(a), Some(b)] => Some((a.clone(), b.clone())),
+            _ => None,
+        };
+        match opcode {
+            1 => cells.set(write, both(&params).and_then(|(a, b)| a.add(&b))),
+            2 => cells
+                .set(write, both(&params).and_then(|(a, b)| a.multiply(&b))),
+            7 | 8 => {
+                let compared = both(&params).and_then(|(a, b)| {
+                    let (a, b) = (a.as_constant()?, b.as_constant()?);
+                    let result = if opcode == 7 { a < b } else { a == b };
+                    Some(Expression::constant(result as i64))
+                });
+                cells.set(write, compared)
+            }
+            5 | 6 => {
+                let condition = concrete(params[0].clone())?;
+                if (condition != 0) == (opcode == 5) {
+                    position = concrete(params[1].clone())?;
+                    continue;
+                }
+            }
+            9 => {
+                base =
+                    offset(base, concrete(params[0].clone())?).map_err(fault)?
+            }
+            _ => {
+                return cells
+                    .get(address)
+                    .ok_or(SymbolicError::Unknown { address })
+            }
+        }
+        position =
+            offset(position, 1 + operation.arity as i64).map_err(fault)?;
+    }
+    Err(SymbolicError::Endless)
+}
+
+/// Values for the patched cells, in order, that make the program halt with
+/// `target` at `address`
+///
+/// When that value is linear in the cells it is solved for the last cell that
+/// matters, every combination of values is swept when it is not or when that
+/// finds nothing. The first
+/// combination in order of the patches is returned.
+pub fn solve(
+    program: &Program,
+    patches: &[Patch],
+    address: i64,
+    target: i64,
+) -> Option<Vec<i64>> {
+    let limits =
+        Limits { max_instructions: Some(MAX_INSTRUCTIONS), ..Limits::none() };
+    let halts_with_target = |runner: &Runner| {
+        runner.state.status == ExitStatus::Halted
+            && runner.program.get(address) == target
+    };
+
+    let addresses = patches.iter().map(|patch| patch.address).collect_vec();
+    let linear = expression(program, &addresses, address)
+        .ok()
+        .and_then(|expression| expression.linear(&addresses));
+    let solved = linear.and_then(|(constant, coefficients)| {
+        solve_linear(constant, &coefficients, patches, target)
+    });
+    // the interpreter wraps around where the expression would not, so values
+    // the equation rules out can still work and are left to the sweep
+    if let Some(values) = solved {
+        let solution = patches
+            .iter()
+            .zip(&values)
+            .map(|(patch, &value)| Patch { values: value..=value, ..*patch })
+            .collect();
+        let sweep = Sweep::new(program.clone(), solution).threads(1);
+        if let Ok(Some(_)) = sweep.limits(limits).first(halts_with_target) {
+            return Some(values);
+        }
+    }
+    Sweep::new(program.clone(), patches.to_vec())
+        .limits(limits)
+        .first(halts_with_target)
+        .ok()?
+        .map(|found| found.values)
+}
+
+fn solve_linear(
+    constant: i64,
+    coefficients: &[i64],
+    patches: &[Patch],
+    target: i64,
+) -> Option<Vec<i64>> {
+    let last = coefficients.iter().rposition(|&coefficient| coefficient != 0);
+    // cells that do not matter take their first value
+    let ranges =
+        patches.iter().zip(coefficients).map(|(patch, &coefficient)| {
+            match coefficient {
+                0 => *patch.values.start()..=*patch.values.start(),
+                _ => patch.values.clone(),
+            }
+        });
+    let last = match last {
+        Some(last) => last,
+        None => {
+            let values = ranges.map(|range| *range.start()).collect_vec();
+            return Some(values).filter(|_| constant == target);
+        }
+    };
+
+    let mut ranges = ranges.collect_vec();
+    let solved = ranges.split_off(last);
+    grid(ranges).find_map(|mut values| {
+        let rest = values
+            .iter()
+            .zip(coefficients)
+            .map(|(&value, &coefficient)| value as i128 * coefficient as i128)
+            .fold(target as i128 - constant as i128, |rest, term| rest - term);
+        let coefficient = coefficients[last] as i128;
+        if rest % coefficient != 0 {
+            return None;
+        }
+        let value = rest / coefficient;
+        if value < i64::MIN as i128 || value > i64::MAX as i128 {
+            return None;
+        }
+        values.push(value as i64);
+        values.extend(solved[1..].iter().map(|range| *range.start()));
+        Some(values).filter(|_| patches[last].values.contains(&(value as i64)))
+    })
+}
+
+/// Every combination of values, the last range changing fastest
+fn grid(
+    ranges: Vec<RangeInclusive<i64>>,
+) -> Box<dyn Iterator<Item = Vec<i64>>> {
+    match ranges.is_empty() {
+        true => Box::new(Some(vec![]).into_iter()),
+        false => Box::new(ranges.into_iter().multi_cartesian_product()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::load;
+
+    // the start of a gravity assist program: [0] = ([1] + [2]) * 3 + 5
+    const LINEAR: &str = "1,0,0,3,1,1,2,3,2,3,17,0,1,0,18,0,99,3,5";
+
+    fn patches(ranges: &[(i64, RangeInclusive<i64>)]) -> Vec<Patch> {
+        ranges
+            .iter()
+            .map(|(address, values)| Patch {
+                address: *address,
+                values: values.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_expression() {
+        let program = load(LINEAR);
+
+        let expression = expression(&program, &[1, 2], 0).unwrap();
+
+        assert_eq!(expression.to_string(), "3*[1] + 3*[2] + 5");
+        assert_eq!(expression.linear(&[1, 2]), Some((5, vec![3, 3])));
+    }
+
+    #[test]
+    fn test_display() {
+        let x = Expression::symbol(1);
+        let y = Expression::symbol(2);
+        let expression = x
+            .multiply(&y)
+            .unwrap()
+            .add(&Expression::constant(-4))
+            .unwrap()
+            .add(&x.multiply(&Expression::constant(-2)).unwrap())
+            .unwrap();
+
+        assert_eq!(expression.to_string(), "[1]*[2] - 2*[1] - 4");
+        assert_eq!(expression.linear(&[1, 2]), None);
+        assert_eq!(Expression::constant(0).to_string(), "0");
+    }
+
+    #[test]
+    fn test_solve_linear() {
+        let program = load(LINEAR);
+        let patches = patches(&[(1, 0..=99), (2, 0..=99)]);
+
+        assert_eq!(solve(&program, &patches, 0, 59), Some(vec![0, 18]));
+        assert_eq!(solve(&program, &patches, 0, 6), None);
+    }
+
+    #[test]
+    fn test_solve_by_search() {
+        // [0] = [9] * [10], not linear
+        let program = program![2, 9, 10, 0, 99, 0, 0, 0, 0, 0, 0];
+        let patches = patches(&[(9, 2..=9), (10, 2..=9)]);
+
+        assert_eq!(solve(&program, &patches, 0, 42), Some(vec![6, 7]));
+        assert_eq!(solve(&program, &patches, 0, 11), None);
+    }
+
+    #[test]
+    fn test_solve_wrapping() {
+        // [0] = [1] * 2^62, which only wraps around to i64::MIN in the
+        // interpreter
+        let program = program![1102, 0, 4611686018427387904, 0, 99];
+        let patches = patches(&[(1, 0..=7)]);
+
+        assert_eq!(solve(&program, &patches, 0, i64::MIN), Some(vec![2]));
+    }
+
+    #[test]
+    fn test_undecided() {
+        // halt with 1 at address 0 when [10] is not 0
+        let program = program![1005, 10, 4, 99, 1101, 1, 0, 0, 99, 0, 0];
+
+        assert_eq!(
+            expression(&program, &[10], 0),
+            Err(SymbolicError::Undecided { position: 0 })
+        );
+        let patches = patches(&[(10, 0..=5)]);
+        assert_eq!(solve(&program, &patches, 0, 1), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_unknown() {
+        // [5] = [[1]], then halt
+        let program = program![1001, 0, 0, 5, 99, 0];
+
+        assert_eq!(
+            expression(&program, &[1], 5),
+            Err(SymbolicError::Unknown { address: 5 })
+        );
+        assert_eq!(
+            expression(&program, &[1], 0),
+            Ok(Expression::constant(1001))
+        );
+    }
+}
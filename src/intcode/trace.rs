@@ -0,0 +1,281 @@
+use intcode::isa::InstructionSet;
+use intcode::{
+    extract_modes, find_value, offset, read, to_address, writing_position,
+    Cell, Memory,
+};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// A single instruction, as it is about to be executed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Event<C = i64> {
+    pub position: i64,
+    pub opcode: i64,
+    /// Values of the parameters that are read, with their modes applied
+    pub operands: Vec<C>,
+    /// Address the instruction writes to, if it writes at all
+    pub write: Option<i64>,
+    pub base: i64,
+}
+
+impl<C: Cell> Event<C> {
+    /// Inspect the instruction at `position` without executing it
+    ///
+    /// Instructions that would fault produce no event, the fault is reported
+    /// by the interpreter itself.
+    pub(super) fn at(
+        program: &Memory<C>,
+        position: i64,
+        base: i64,
+        instructions: &InstructionSet<C>,
+    ) -> Option<Event<C>> {
+        let instruction = to_address(&read(position, program).ok()?).ok()?;
+        let (modes, opcode) = extract_modes(instruction).ok()?;
+        let operation = instructions.get(opcode)?;
+
+        let mut operands = Vec::new();
+        let mut write = None;
+        for (index, mode) in modes.iter().enumerate().take(operation.arity) {
+            let offset = offset(position, 1 + index as i64).ok()?;
+            if operation.write == Some(index) {
+                write =
+                    Some(writing_position(offset, mode, base, program).ok()?);
+            } else {
+                operands.push(find_value(offset, mode, base, program).ok()?);
+            }
+        }
+        Some(Event { position, opcode, operands, write, base })
+    }
+}
+
+/// Gets told about every instruction a traced program executes
+pub trait Tracer<C = i64> {
+    fn trace(&mut self, event: &Event<C>);
+
+    /// Tracers that are not enabled are never handed an event, which spares
+    /// the interpreter from inspecting every instruction
+    fn enabled(&self) -> bool {
+        true
+    }
+}
+
+/// Traces nothing, used when running without a tracer
+impl<C> Tracer<C> for () {
+    fn trace(&mut self, _: &Event<C>) {}
+
+    fn enabled(&self) -> bool {
+        false
+    }
+}
+
+impl<C, F: FnMut(&Event<C>)> Tracer<C> for F {
+    fn trace(&mut self, event: &Event<C>) {
+        self(event)
+    }
+}
+
+/// Writes one line per executed instruction, named after the instruction set
+/// the program runs with
+///
+/// Write errors are dropped, a broken trace should not stop the program.
+pub struct Log<'a, W: Write, C: Cell = i64> {
+    writer: W,
+    instructions: &'a InstructionSet<C>,
+}
+
+impl<'a, W: Write, C: Cell> Log<'a, W, C> {
+    pub fn new(writer: W, instructions: &'a InstructionSet<C>) -> Self {
+        Log { writer, instructions }
+    }
+}
+
+impl<'a, C: Cell, W: Write> Tracer<C> for Log<'a, W, C> {
+    fn trace(&mut self, event: &Event<C>) {
+        let mut text = self
+            .instructions
+            .get(event.opcode)
+            .map_or("???", |operation| operation.mnemonic)
+            .to_string();
+        if !event.operands.is_empty() {
+            let operands: Vec<_> =
+                event.operands.iter().map(|value| value.to_string()).collect();
+            text += &format!(" {}", operands.join(", "));
+        }
+        if let Some(address) = event.write {
+            text += &format!(" -> [{}]", address);
+        }
+        writeln!(
+            self.writer,
+            "{:>5}: {:<24} rb={}",
+            event.position, text, event.base
+        )
+        .ok();
+    }
+}
+
+/// Counts how often every opcode and every address is executed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Profiler {
+    pub opcodes: HashMap<i64, u64>,
+    pub addresses: HashMap<i64, u64>,
+}
+
+impl Profiler {
+    pub fn new() -> Profiler {
+        Profiler::default()
+    }
+
+    /// Amount of instructions executed
+    pub fn total(&self) -> u64 {
+        self.opcodes.values().sum()
+    }
+
+    /// The `count` most executed addresses, most executed first
+    pub fn hottest(&self, count: usize) -> Vec<(i64, u64)> {
+        let mut addresses: Vec<_> =
+            self.addresses.iter().map(|(&a, &n)| (a, n)).collect();
+        addresses.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        addresses.truncate(count);
+        addresses
+    }
+
+    /// A printable summary of the counts per opcode of the instruction set
+    /// and the hottest addresses
+    pub fn report<C: Cell>(&self, instructions: &InstructionSet<C>) -> String {
+        let mut report = format!("{} instructions executed\n", self.total());
+        for operation in instructions.iter() {
+            if let Some(count) = self.opcodes.get(&operation.opcode) {
+                report += &format!("{:>5} {:>12}\n", operation.mnemonic, count);
+            }
+        }
+        report += "hottest addresses\n";
+        for (address, count) in self.hottest(10) {
+            report += &format!("{:>5} {:>12}\n", address, count);
+        }
+        report
+    }
+}
+
+impl<C> Tracer<C> for Profiler {
+    fn trace(&mut self, event: &Event<C>) {
+        *self.opcodes.entry(event.opcode).or_insert(0) += 1;
+        *self.addresses.entry(event.position).or_insert(0) += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::isa::{EXTENDED, STANDARD};
+    use intcode::{io, Runner};
+
+    #[test]
+    fn test_event() {
+        let program = program![21101, 5, -3, 7, 204, -1, 99];
+
+        assert_eq!(
+            Event::at(&program, 0, 10, &STANDARD),
+            Some(Event {
+                position: 0,
+                opcode: 1,
+                operands: vec![5, -3],
+                write: Some(17),
+                base: 10,
+            })
+        );
+        assert_eq!(
+            Event::at(&program, 4, 4, &STANDARD),
+            Some(Event {
+                position: 4,
+                opcode: 4,
+                operands: vec![7],
+                write: None,
+                base: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn test_event_for_invalid_instruction() {
+        assert_eq!(Event::at(&program![42], 0, 0, &STANDARD), None);
+        assert_eq!(Event::at(&program![11101, 1, 1, 0], 0, 0, &STANDARD), None);
+    }
+
+    #[test]
+    fn test_log() {
+        let program = program![3, 9, 1002, 9, 2, 9, 4, 9, 99, 0];
+        let mut trace = Vec::new();
+
+        Runner::new(program)
+            .run_traced(
+                &mut io::iter(vec![21]),
+                &mut Vec::new(),
+                &mut Log::new(&mut trace, &STANDARD),
+            )
+            .unwrap();
+
+        let trace = String::from_utf8(trace).unwrap();
+        assert_eq!(
+            trace.lines().collect::<Vec<_>>(),
+            vec![
+                "    0: IN -> [9]                rb=0",
+                "    2: MUL 21, 2 -> [9]         rb=0",
+                "    6: OUT 42                   rb=0",
+                "    8: HLT                      rb=0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_profiler() {
+        // count down from 3
+        let program = program![3, 11, 1001, 11, -1, 11, 1005, 11, 2, 99, 0, 0];
+        let mut profiler = Profiler::new();
+
+        Runner::new(program)
+            .run_traced(&mut io::iter(vec![3]), &mut Vec::new(), &mut profiler)
+            .unwrap();
+
+        assert_eq!(profiler.total(), 8);
+        assert_eq!(profiler.opcodes[&1], 3);
+        assert_eq!(profiler.opcodes[&5], 3);
+        assert_eq!(profiler.hottest(2), vec![(2, 3), (6, 3)]);
+    }
+
+    #[test]
+    fn test_extended_instructions() {
+        // CHK 0, 5 then EXIT 7
+        let program = program![1122, 0, 5, 121, 7, 99];
+        let mut trace = Vec::new();
+        let mut profiler = Profiler::new();
+
+        Runner::new(program.clone())
+            .run_traced_with(
+                &mut io::iter(vec![]),
+                &mut Vec::new(),
+                &mut Log::new(&mut trace, &EXTENDED),
+                &EXTENDED,
+            )
+            .unwrap();
+        Runner::new(program)
+            .run_traced_with(
+                &mut io::iter(vec![]),
+                &mut Vec::new(),
+                &mut profiler,
+                &EXTENDED,
+            )
+            .unwrap();
+
+        let trace = String::from_utf8(trace).unwrap();
+        assert_eq!(
+            trace.lines().collect::<Vec<_>>(),
+            vec![
+                "    0: CHK 0, 5                 rb=0",
+                "    3: EXIT 7                   rb=0",
+            ]
+        );
+        let report = profiler.report(&EXTENDED);
+        assert!(report.contains("  CHK            1\n"));
+        assert!(report.contains(" EXIT            1\n"));
+    }
+}
@@ -5,7 +5,6 @@ extern crate itertools;
 #[macro_use(s)]
 #[cfg_attr(test, macro_use(array))]
 extern crate ndarray;
-extern crate console;
 #[cfg(test)]
 extern crate mockall;
 extern crate num;
@@ -15,52 +14,737 @@ extern crate petgraph;
 extern crate strum;
 extern crate strum_macros;
 
-use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::hint;
+use std::io;
+use std::mem;
+use std::ops::RangeInclusive;
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
+mod answers;
+mod bench;
+mod cli;
+mod input;
 #[macro_use]
 mod intcode;
+mod solution;
+
+use answers::{Check, Manifest};
+use bench::Timing;
+use cli::{CliError, Format};
+use input::{InputError, Provider};
+use solution::{Answer, Solution};
+use std::path::Path;
 
 fn main() {
-    let mut args = env::args();
-    args.next();
+    let mut args = env::args().skip(1);
+    let result = match args.next() {
+        Some(command) => match command.as_str() {
+            "run" => run(&args.collect::<Vec<_>>()),
+            "record" => record(&args.collect::<Vec<_>>()),
+            "bench" => bench(&args.collect::<Vec<_>>()),
+            "list" => {
+                list();
+                Ok(())
+            }
+            _ => tool(&command, &mut args),
+        },
+        None => Err(CliError::NoCommand),
+    };
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        if error.is_usage() {
+            eprintln!("\n{}", cli::USAGE);
+        }
+        process::exit(1);
+    }
+}
+
+/// Run the parts of the days given in the arguments
+fn run(args: &[String]) -> Result<(), CliError> {
+    let days = days();
+    let names: Vec<_> = days.iter().map(|day| day.name).collect();
+    let run = cli::parse_run(cli::Command::Run, args, &names)?;
+    let provider = run.input.clone().unwrap_or_else(Provider::from_env);
+
+    let mut json = Vec::new();
+    let mut wrong = 0;
+    for name in &run.days {
+        let day = days.iter().find(|day| day.name == *name).unwrap();
+        let input = provider.read(day.name, day.input)?;
+        let answers = solve(day, &input, run.part);
+        let checks = match run.check {
+            true => check(day.name, &input, &answers)?,
+            false => Vec::new(),
+        };
+        wrong += checks
+            .iter()
+            .filter(|check| matches!(check, Check::Wrong { .. }))
+            .count();
+        match run.format {
+            Format::Text => {
+                for (index, (part, answer)) in answers.iter().enumerate() {
+                    let check = match checks.get(index) {
+                        Some(Check::Wrong { expected }) => {
+                            format!(" (wrong, expected {})", expected)
+                        }
+                        Some(check) => format!(" ({})", check),
+                        None => String::new(),
+                    };
+                    println!("{} part {}{}: {}", day.name, part, check, answer);
+                }
+            }
+            Format::Json => json.push(cli::json(day.name, &answers, &checks)),
+        }
+    }
+    if run.format == Format::Json {
+        println!("[{}]", json.join(",\n "));
+    }
+    match wrong {
+        0 => Ok(()),
+        wrong => Err(CliError::WrongAnswers(wrong)),
+    }
+}
+
+/// Run the days given in the arguments and record their answers in the
+/// manifests, keeping the other part when the input did not change
+fn record(args: &[String]) -> Result<(), CliError> {
+    let days = days();
+    let names: Vec<_> = days.iter().map(|day| day.name).collect();
+    let run = cli::parse_run(cli::Command::Record, args, &names)?;
+    let provider = run.input.unwrap_or_else(Provider::from_env);
+    let directory = Path::new(answers::DIRECTORY);
+
+    for name in &run.days {
+        let day = days.iter().find(|day| day.name == *name).unwrap();
+        let input = provider.read(day.name, day.input)?;
+        let mut manifest = match Manifest::load(directory, day.name)? {
+            Some(manifest)
+                if manifest.input == answers::fingerprint(&input) =>
+            {
+                manifest
+            }
+            _ => Manifest::new(&input),
+        };
+        for (part, answer) in solve(day, &input, run.part) {
+            println!("{} part {}: {}", day.name, part, answer);
+            manifest.set(part, answer);
+        }
+        manifest.save(directory, day.name)?;
+        println!("recorded {}", Manifest::path(directory, day.name).display());
+    }
+    Ok(())
+}
+
+/// Time the parts of the days given in the arguments, reading every input
+/// once before running its day
+fn bench(args: &[String]) -> Result<(), CliError> {
+    let days = days();
+    let names: Vec<_> = days.iter().map(|day| day.name).collect();
+    let run = cli::parse_run(cli::Command::Bench, args, &names)?;
+    let provider = run.input.unwrap_or_else(Provider::from_env);
+    let parts = run.part.map_or(vec![1, 2], |part| vec![part]);
 
-    let modules = create_modules();
-    while let Some(arg) = args.next() {
-        modules.run(&arg);
+    let mut timings = Vec::new();
+    for name in &run.days {
+        let day = days.iter().find(|day| day.name == *name).unwrap();
+        let input = provider.read(day.name, day.input)?;
+        for &part in &parts {
+            let times = (0..run.runs)
+                .map(|_| {
+                    let start = Instant::now();
+                    hint::black_box(
+                        day.solution.solve(part, hint::black_box(&input)),
+                    );
+                    start.elapsed()
+                })
+                .collect();
+            timings.push(Timing { day: day.name, part, times });
+        }
+    }
+    match run.format {
+        Format::Text => print!("{}", bench::markdown(&timings)),
+        Format::Json => println!("{}", bench::json(&timings)),
     }
+    Ok(())
+}
+
+/// The answers to the parts of a day, only to `part` when given
+fn solve(day: &Day, input: &str, part: Option<usize>) -> Vec<(usize, Answer)> {
+    let parts = part.map_or(vec![1, 2], |part| vec![part]);
+    parts
+        .into_iter()
+        .map(|part| (part, day.solution.solve(part, input)))
+        .collect()
 }
 
-type Run = fn();
+/// Compare the answers of a day with its manifest
+fn check(
+    day: &str,
+    input: &str,
+    answers: &[(usize, Answer)],
+) -> Result<Vec<Check>, CliError> {
+    let manifest = Manifest::load(Path::new(answers::DIRECTORY), day)?;
+    Ok(answers
+        .iter()
+        .map(|(part, answer)| match manifest {
+            Some(ref manifest) => manifest.check(input, *part, answer),
+            None => Check::Unrecorded,
+        })
+        .collect())
+}
 
-struct Modules {
-    runners: HashMap<String, Run>,
+/// Print the name of every day
+fn list() {
+    for day in days() {
+        println!("{}", day.name);
+    }
 }
 
-impl Modules {
-    fn add_module(&mut self, name: String, func: Run) {
-        self.runners.insert(name, func);
+/// Run one of the Intcode tools, which take their arguments in a fixed order
+fn tool<I: Iterator<Item = String>>(
+    command: &str,
+    args: &mut I,
+) -> Result<(), CliError> {
+    let mut arg = |name: &str| {
+        args.next().ok_or_else(|| {
+            CliError::MissingValue(format!("{} <{}>", command, name))
+        })
+    };
+    match command {
+        "ascii" => ascii(&arg("program")?),
+        "asm" => asm(&arg("source")?),
+        "disasm" => disasm(&arg("program")?),
+        "debug" => debug(&arg("program")?),
+        "trace" => trace(&arg("program")?, &arg("inputs")?),
+        "profile" => profile(&arg("program")?, &arg("inputs")?),
+        "exec" => exec(&arg("program")?, &arg("inputs")?),
+        "exec-as" => exec_as(&arg("cells")?, &arg("program")?, &arg("inputs")?),
+        "sweep" => sweep(
+            &arg("program")?,
+            &arg("inputs")?,
+            &arg("patches")?,
+            &arg("objective")?,
+        ),
+        "phases" => phases(
+            &arg("program")?,
+            &arg("amplifiers")?,
+            &arg("phases")?,
+            &arg("csv")?,
+        ),
+        "bench-intcode" => bench_intcode(&arg("program")?, &arg("inputs")?),
+        "snapshot" => {
+            snapshot(&arg("program")?, &arg("inputs")?, &arg("snapshot")?)
+        }
+        "resume" => resume(&arg("snapshot")?, &arg("inputs")?),
+        _ => Err(CliError::UnknownCommand(command.to_string())),
     }
+}
+
+fn read_file(path: &str) -> Result<String, CliError> {
+    fs::read_to_string(path).map_err(|error| {
+        CliError::Input(InputError { path: path.to_string(), error })
+    })
+}
+
+fn read_program(path: &str) -> Result<intcode::Program, CliError> {
+    read_program_as(path)
+}
+
+/// The program in the file, every cell of which has to be a number
+fn read_program_as<C: intcode::Cell>(
+    path: &str,
+) -> Result<intcode::Memory<C>, CliError> {
+    intcode::parse_as(&read_file(path)?)
+        .map_err(|error| CliError::Program(path.to_string(), error))
+}
+
+/// A fault of the program read from `path`
+fn failed(path: &str) -> impl Fn(intcode::IntcodeError) -> CliError + '_ {
+    move |error| CliError::Intcode(path.to_string(), error)
+}
+
+/// Play a text based Intcode program from the given file in the terminal
+fn ascii(path: &str) -> Result<(), CliError> {
+    let program = read_program(path)?;
+    let stdin = io::stdin();
+    intcode::ascii::interact(program, stdin.lock(), &mut io::stdout())
+        .map_err(CliError::Terminal)
+}
+
+/// Print the Intcode program assembled from the given file
+fn asm(path: &str) -> Result<(), CliError> {
+    let source = read_file(path)?;
+    let program = intcode::asm::assemble(&source)
+        .map_err(|error| CliError::Assembly(path.to_string(), error))?;
+    println!("{}", intcode::dump(&program));
+    Ok(())
+}
+
+/// Print an annotated listing of the Intcode program in the given file, which
+/// may use the extended instruction set
+fn disasm(path: &str) -> Result<(), CliError> {
+    let program = read_program(path)?;
+    print!("{}", intcode::disasm::listing(&program, &intcode::isa::EXTENDED));
+    Ok(())
+}
+
+/// Step through the Intcode program in the given file with the extended
+/// instruction set, reading debugger commands from stdin
+fn debug(path: &str) -> Result<(), CliError> {
+    let mut debugger = intcode::debugger::Debugger::new(
+        read_program(path)?,
+        &intcode::isa::EXTENDED,
+    );
+    let stdin = io::stdin();
+    debugger.run(stdin.lock(), &mut io::stdout()).map_err(CliError::Terminal)
+}
+
+/// Print every instruction the Intcode program in the given file executes
+/// with the extended instruction set, feeding it the comma separated inputs
+fn trace(path: &str, inputs: &str) -> Result<(), CliError> {
+    let instructions = &intcode::isa::EXTENDED;
+    let runner = intcode::Runner::new(read_program(path)?);
+    let mut log = intcode::trace::Log::new(io::stdout(), instructions);
+    let mut outputs = Vec::new();
+    runner
+        .run_traced_with(
+            &mut intcode::io::iter(parse_inputs(inputs)?),
+            &mut outputs,
+            &mut log,
+            instructions,
+        )
+        .and_then(intcode::Runner::or_fault)
+        .map_err(failed(path))?;
+    println!("outputs: {:?}", outputs);
+    Ok(())
+}
+
+/// Print how often every opcode and address of the Intcode program in the
+/// given file is executed with the extended instruction set, feeding it the
+/// comma separated inputs
+fn profile(path: &str, inputs: &str) -> Result<(), CliError> {
+    let instructions = &intcode::isa::EXTENDED;
+    let runner = intcode::Runner::new(read_program(path)?);
+    let mut profiler = intcode::trace::Profiler::new();
+    runner
+        .run_traced_with(
+            &mut intcode::io::iter(parse_inputs(inputs)?),
+            &mut Vec::new(),
+            &mut profiler,
+            instructions,
+        )
+        .and_then(intcode::Runner::or_fault)
+        .map_err(failed(path))?;
+    print!("{}", profiler.report(instructions));
+    Ok(())
+}
+
+/// Run the Intcode program in the given file with the extended instruction
+/// set, feeding it the comma separated inputs
+fn exec(path: &str, inputs: &str) -> Result<(), CliError> {
+    let runner = intcode::Runner::new(read_program(path)?);
+    let mut outputs = Vec::new();
+    let runner = runner
+        .run_with(
+            &mut intcode::io::iter(parse_inputs(inputs)?),
+            &mut outputs,
+            &intcode::isa::EXTENDED,
+        )
+        .map_err(failed(path))?;
+    println!("outputs: {:?}", outputs);
+    println!("status: {:?}", runner.state.status);
+    runner.or_fault().map(|_| ()).map_err(failed(path))
+}
 
-    fn run(&self, name: &String) {
-        let runner = self.runners.get(name).unwrap();
-        runner();
+/// Run the Intcode program in the given file with a different type of cell:
+/// `checked` faults on overflow, `i128` and `bigint` hold larger numbers
+fn exec_as(cells: &str, path: &str, inputs: &str) -> Result<(), CliError> {
+    match cells {
+        "i64" => exec_cells::<i64>(path, inputs),
+        "checked" => exec_cells::<intcode::cell::Checked>(path, inputs),
+        "i128" => exec_cells::<i128>(path, inputs),
+        "bigint" => exec_cells::<num::BigInt>(path, inputs),
+        _ => Err(CliError::InvalidArgument(format!(
+            "unknown cell type {}, use i64, checked, i128 or bigint",
+            cells
+        ))),
     }
 }
 
-macro_rules! modules {
-    ($($mod:ident,)*) => {
-        $( mod $mod; )*
+fn exec_cells<C: intcode::Cell>(
+    path: &str,
+    inputs: &str,
+) -> Result<(), CliError> {
+    let runner = intcode::Runner::new(read_program_as::<C>(path)?);
+    let mut outputs = Vec::new();
+    let runner = runner
+        .run(&mut intcode::io::iter(parse_inputs::<C>(inputs)?), &mut outputs)
+        .map_err(failed(path))?;
+    let outputs: Vec<_> = outputs.iter().map(C::to_string).collect();
+    println!("outputs: [{}]", outputs.join(", "));
+    println!("status: {:?}", runner.state.status);
+    runner.or_fault().map(|_| ()).map_err(failed(path))
+}
+
+/// Run the Intcode program in the given file on the comma separated inputs with
+/// every combination of the comma separated patches like `1=0..99`, printing
+/// the combinations that meet the objective: `<address>=<value>` after halting
+/// or `out=<value>` as the last output
+fn sweep(
+    path: &str,
+    inputs: &str,
+    patches: &str,
+    objective: &str,
+) -> Result<(), CliError> {
+    let program = read_program(path)?;
+    let patches =
+        patches.split(',').map(parse_patch).collect::<Result<Vec<_>, _>>()?;
+    let invalid = || {
+        CliError::InvalidArgument(format!(
+            "objectives look like 0=19690720 or out=42, not {}",
+            objective
+        ))
+    };
+    let (target, value) = objective.split_once('=').ok_or_else(invalid)?;
+    let value: i64 = value.trim().parse().map_err(|_| invalid())?;
+    let address: Option<i64> = match target {
+        "out" => None,
+        address => Some(address.trim().parse().map_err(|_| invalid())?),
+    };
 
-        fn create_modules() -> Modules {
-            let mut modules = Modules { runners: HashMap::new() };
-            $( modules.add_module(stringify!($mod).to_string(), $mod::run); )*
-            modules
+    let limits = intcode::Limits {
+        max_instructions: Some(1 << 24),
+        ..intcode::Limits::none()
+    };
+    let sweep = intcode::sweep::Sweep::new(program, patches)
+        .inputs(parse_inputs(inputs)?)
+        .limits(limits);
+    let unsweepable = |error: intcode::sweep::SweepError| {
+        CliError::InvalidArgument(error.to_string())
+    };
+    let combinations = sweep
+        .combinations()
+        .ok_or(intcode::sweep::SweepError::TooManyCombinations)
+        .map_err(unsweepable)?;
+    let matches = sweep.all(|runner| match address {
+        Some(address) => {
+            runner.state.status == intcode::ExitStatus::Halted
+                && runner.program.get(address) == value
         }
+        None => runner.outputs.last() == Some(&value),
+    });
+    let matches = matches.map_err(unsweepable)?;
+    for found in &matches {
+        let values: Vec<_> = found.values.iter().map(i64::to_string).collect();
+        println!("{}", values.join(","));
+    }
+    println!("{} of {} combinations", matches.len(), combinations);
+    Ok(())
+}
+
+/// A patch like `1=0..99` or `1=12`
+fn parse_patch(patch: &str) -> Result<intcode::sweep::Patch, CliError> {
+    let invalid = || {
+        CliError::InvalidArgument(format!(
+            "patches look like 1=0..99 or 1=12, not {}",
+            patch
+        ))
+    };
+    let (address, values) = patch.split_once('=').ok_or_else(invalid)?;
+    let address: i64 = address.trim().parse().map_err(|_| invalid())?;
+    if address < 0 {
+        return Err(CliError::InvalidArgument(format!(
+            "cannot patch negative address {}",
+            address
+        )));
+    }
+    let values = parse_range(values).ok_or_else(invalid)?;
+    Ok(intcode::sweep::Patch { address, values })
+}
+
+/// A range like `0..99`, including the end, or a single number
+fn parse_range(range: &str) -> Option<RangeInclusive<i64>> {
+    let number = |text: &str| text.trim().parse::<i64>().ok();
+    match range.split_once("..") {
+        Some((start, end)) => Some(number(start)?..=number(end)?),
+        None => number(range).map(|number| number..=number),
+    }
+}
+
+/// Run the amplifier program in the given file on the given amount of
+/// amplifiers with every sequence of phases from a range like `5..9`, writing
+/// the signal of every sequence to the CSV file and printing the highest
+fn phases(
+    path: &str,
+    amplifiers: &str,
+    phases: &str,
+    csv_path: &str,
+) -> Result<(), CliError> {
+    let amplifier = read_program(path)?;
+    let amount = match amplifiers.parse() {
+        Ok(amount) if amount > 0 => amount,
+        _ => {
+            return Err(CliError::InvalidArgument(format!(
+                "the amount of amplifiers must be a positive number, not {}",
+                amplifiers
+            )))
+        }
+    };
+    let range = parse_range(phases).ok_or_else(|| {
+        CliError::InvalidArgument(format!(
+            "phases look like 5..9, not {}",
+            phases
+        ))
+    })?;
+
+    let settings =
+        day_07::signals(&amplifier, amount, range).map_err(failed(path))?;
+    fs::File::create(csv_path)
+        .and_then(|file| day_07::write_csv(&settings, io::BufWriter::new(file)))
+        .map_err(|error| CliError::Write(csv_path.to_string(), error))?;
+
+    match day_07::highest(&settings) {
+        Some(best) => {
+            println!(
+                "highest signal {} with phases {:?}",
+                best.signal, best.phases
+            )
+        }
+        None => println!(
+            "no sequence of {} phases in {} sends a signal",
+            amount, phases
+        ),
+    }
+    Ok(())
+}
+
+/// Compare the time the Intcode program in the given file takes with and
+/// without caching decoded instructions, feeding it the comma separated inputs
+fn bench_intcode(path: &str, inputs: &str) -> Result<(), CliError> {
+    const RUNS: usize = 20;
+
+    let runner = intcode::Runner::new(read_program(path)?);
+    let inputs = parse_inputs(inputs)?;
+
+    let fastest = |cached: bool| -> Result<Duration, CliError> {
+        let times = (0..RUNS)
+            .map(|_| {
+                let runner = runner.clone();
+                let mut source = intcode::io::iter(inputs.clone());
+                let start = Instant::now();
+                if cached {
+                    runner.run(&mut source, &mut Vec::new())
+                } else {
+                    runner.run_uncached(&mut source, &mut Vec::new())
+                }
+                .and_then(intcode::Runner::or_fault)
+                .map_err(failed(path))?;
+                Ok(start.elapsed())
+            })
+            .collect::<Result<Vec<_>, CliError>>()?;
+        Ok(times.into_iter().min().unwrap())
+    };
+
+    let uncached = fastest(false)?;
+    let cached = fastest(true)?;
+    println!("uncached: {:?}", uncached);
+    println!("cached:   {:?}", cached);
+    println!("speedup:  {:.2}x", uncached.as_secs_f64() / cached.as_secs_f64());
+    Ok(())
+}
+
+/// Run the Intcode program in the given file on the comma separated inputs and
+/// save the state it ends up in
+fn snapshot(
+    path: &str,
+    inputs: &str,
+    snapshot_path: &str,
+) -> Result<(), CliError> {
+    let runner = intcode::Runner::new(read_program(path)?);
+    save(run_with_inputs(runner, inputs, path)?, snapshot_path)
+}
+
+/// Continue a program from a snapshot with the comma separated inputs, saving
+/// the state it ends up in back to the snapshot
+fn resume(snapshot_path: &str, inputs: &str) -> Result<(), CliError> {
+    let runner = fs::File::open(snapshot_path)
+        .map_err(intcode::snapshot::SnapshotError::Io)
+        .and_then(intcode::snapshot::load_snapshot)
+        .map_err(|error| {
+            CliError::Snapshot(snapshot_path.to_string(), error)
+        })?;
+    save(run_with_inputs(runner, inputs, snapshot_path)?, snapshot_path)
+}
+
+/// Print the outputs a runner held on to and everything it outputs next
+///
+/// Programs that run for too long or use too much memory are stopped, their
+/// snapshot can be resumed. Faults of the program read from `path` are
+/// returned once the outputs are printed.
+fn run_with_inputs(
+    mut runner: intcode::Runner,
+    inputs: &str,
+    path: &str,
+) -> Result<intcode::Runner, CliError> {
+    let limits = intcode::Limits {
+        max_memory: Some(1 << 24),
+        deadline: Some(Instant::now() + Duration::from_secs(10)),
+        ..intcode::Limits::none()
     };
+    let mut outputs = mem::take(&mut runner.outputs);
+    let runner = runner
+        .run_limited(
+            &mut intcode::io::iter(parse_inputs(inputs)?),
+            &mut outputs,
+            &limits,
+        )
+        .map_err(failed(path))?;
+    println!("outputs: {:?}", outputs);
+    println!("status: {:?}", runner.state.status);
+    runner.or_fault().map_err(failed(path))
 }
 
-modules![
+/// Snapshots with a `.txt` extension are saved as text, others as binary
+fn save(runner: intcode::Runner, path: &str) -> Result<(), CliError> {
+    let format = if path.ends_with(".txt") {
+        intcode::snapshot::Format::Text
+    } else {
+        intcode::snapshot::Format::Binary
+    };
+    fs::File::create(path)
+        .and_then(|file| {
+            intcode::snapshot::save_snapshot(&runner, file, format)
+        })
+        .map_err(|error| CliError::Write(path.to_string(), error))
+}
+
+fn parse_inputs<T: FromStr>(inputs: &str) -> Result<Vec<T>, CliError> {
+    inputs
+        .split(',')
+        .filter(|input| !input.trim().is_empty())
+        .map(|input| {
+            input.trim().parse().map_err(|_| {
+                CliError::InvalidArgument(format!(
+                    "inputs must be numbers, not {}",
+                    input
+                ))
+            })
+        })
+        .collect()
+}
+
+/// The puzzle of a day, with the input embedded for it
+struct Day {
+    name: &'static str,
+    input: &'static str,
+    solution: &'static dyn Solution,
+}
+
+macro_rules! days {
+    ($($day:ident,)*) => {
+        $( mod $day; )*
+
+        /// Every day, in order
+        fn days() -> Vec<Day> {
+            vec![$(
+                Day {
+                    name: stringify!($day),
+                    input: $day::INPUT,
+                    solution: &$day::Puzzle,
+                },
+            )*]
+        }
+
+        /// Every day against the answers recorded for its input
+        #[cfg(test)]
+        mod regression {
+            $(
+                #[test]
+                fn $day() {
+                    super::check_recorded(stringify!($day));
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(test)]
+fn check_recorded(name: &str) {
+    let day = days().into_iter().find(|day| day.name == name).unwrap();
+    let directory =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(answers::DIRECTORY);
+    let manifest =
+        Manifest::load(&directory, name).unwrap().expect("No answers recorded");
+
+    for (part, answer) in solve(&day, day.input, None) {
+        assert_eq!(
+            manifest.check(day.input, part, &answer),
+            Check::Correct,
+            "{} part {} answered {}",
+            name,
+            part,
+            answer
+        );
+    }
+}
+
+days![
     day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09,
     day_10, day_11, day_12, day_13, day_14, day_15, day_16,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_with(command: &str, args: &[&str]) -> Result<(), CliError> {
+        tool(command, &mut args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_tool_errors() {
+        let path = env::temp_dir().join("aoc_tool_test.txt");
+        fs::write(&path, "7,0,99").unwrap();
+        let path = path.to_string_lossy();
+
+        assert!(matches!(
+            tool_with("exec", &[&path]),
+            Err(CliError::MissingValue(_))
+        ));
+        assert!(matches!(
+            tool_with("exec", &[&path, ""]),
+            Err(CliError::Intcode(
+                _,
+                intcode::IntcodeError::UnknownOpcode { .. }
+            ))
+        ));
+        assert!(matches!(
+            tool_with("exec-as", &["foo", &path, ""]),
+            Err(CliError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            tool_with("trace", &[&path, "1,x"]),
+            Err(CliError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            tool_with("profile", &["no/such/program.txt", ""]),
+            Err(CliError::Input(_))
+        ));
+
+        let path = env::temp_dir().join("aoc_tool_test_bad.txt");
+        fs::write(&path, "1,x,0,0,99").unwrap();
+        let path = path.to_string_lossy();
+        assert!(matches!(
+            tool_with("disasm", &[&path]),
+            Err(CliError::Program(_, intcode::ParseError::Value(_)))
+        ));
+        assert!(matches!(
+            tool_with("exec-as", &["i128", &path, ""]),
+            Err(CliError::Program(..))
+        ));
+    }
+}
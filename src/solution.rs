@@ -0,0 +1,137 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+/// The answer to one part of a puzzle
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    /// A picture with a line per row, the answer is what it spells
+    Image(Vec<String>),
+}
+
+impl Answer {
+    /// An image from rows of pixels, drawing the lit ones as blocks
+    pub fn image<R, P>(rows: R) -> Answer
+    where
+        R: IntoIterator<Item = P>,
+        P: IntoIterator<Item = bool>,
+    {
+        let draw = |lit| if lit { '\u{2588}' } else { ' ' };
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(draw).collect::<String>())
+            .collect();
+        Answer::Image(rows)
+    }
+
+    /// The answer as a JSON value, images become an array of rows
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Number(number) => number.to_string(),
+            Answer::Text(text) => json_string(text),
+            Answer::Image(rows) => {
+                let rows: Vec<_> =
+                    rows.iter().map(|row| json_string(row)).collect();
+                format!("[{}]", rows.join(","))
+            }
+        }
+    }
+}
+
+/// Images start on a line of their own
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Image(rows) => {
+                rows.iter().try_for_each(|row| write!(f, "\n{}", row))
+            }
+        }
+    }
+}
+
+macro_rules! number_answers {
+    ($($number:ty)*) => {
+        $(
+            impl From<$number> for Answer {
+                fn from(number: $number) -> Self {
+                    Answer::Number(
+                        i64::try_from(number).expect("Answer does not fit"),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+number_answers!(i32 i64 u32 u64 usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+/// A puzzle of a day, solved for any input of it
+pub trait Solution {
+    fn part1(&self, input: &str) -> Answer;
+    fn part2(&self, input: &str) -> Answer;
+
+    /// The answer to part 1 or 2
+    fn solve(&self, part: usize, input: &str) -> Answer {
+        match part {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => panic!("There is no part {}", part),
+        }
+    }
+}
+
+/// A JSON string literal holding `text`
+pub fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                json.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image() {
+        let answer = Answer::image(vec![vec![true, false], vec![false, true]]);
+        assert_eq!(
+            answer,
+            Answer::Image(vec![
+                "\u{2588} ".to_string(),
+                " \u{2588}".to_string()
+            ])
+        );
+        assert_eq!(answer.to_string(), "\n\u{2588} \n \u{2588}");
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(Answer::from(42usize).to_json(), "42");
+        assert_eq!(Answer::from("0\"1\\".to_string()).to_json(), r#""0\"1\\""#);
+        assert_eq!(
+            Answer::Image(vec!["# ".to_string(), " #".to_string()]).to_json(),
+            r##"["# "," #"]"##
+        );
+        assert_eq!(json_string("a\tb\n"), r#""a\u0009b\n""#);
+    }
+}